@@ -6,6 +6,7 @@ use http::Extensions;
 use serde::{Deserialize, Serialize};
 
 use futures::future::BoxFuture;
+use trek::extract::{with, Path};
 use trek::middleware::Logger;
 use trek::middleware::{Cookie, CookiesContextExt, CookiesMiddleware};
 use trek::{into_box_dyn_handler, json, Context, Middleware, Resources, Response, Trek};
@@ -114,9 +115,14 @@ async fn main() -> Result<(), std::io::Error> {
                 ),
             ],
         )
-        .get("/users/:name/repos/:repo/issues/:id", |cx: ContextState| {
-            async move { json(&cx.params::<UserInfo>().unwrap()) }
-        })
+        .get(
+            "/users/:name/repos/:repo/issues/:id<u64>",
+            |cx: ContextState| async move { json(&cx.params::<UserInfo>().unwrap()) },
+        )
+        .get(
+            "/repos/:repo",
+            with(|Path(repo): Path<String>| async move { "repo: ".to_owned() + &repo }),
+        )
         .scope("/admin", |a| {
             a.middleware(MiddlewareC {});
             a.get("", |_| async { "hello /admin" });
@@ -0,0 +1,223 @@
+//! Streaming request bodies.
+
+use bytes::{Bytes, BytesMut};
+use futures::{ready, Future, Stream};
+use futures_timer::Delay;
+use hyper::Body;
+use serde::de::DeserializeOwned;
+use std::{
+    io::Result,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::Rejection;
+
+/// The chunks of the request body, see `Context::body_stream`.
+///
+/// Chunks are only pulled from the connection when the stream is polled, so
+/// a slow consumer slows down the client instead of buffering the body.
+/// The stream ends with an error when the bytes received cross the limit, if
+/// any, or when no chunk arrives within the timeout.
+pub struct BodyStream {
+    body: Body,
+    limit: Option<usize>,
+    received: usize,
+    timeout: Option<Duration>,
+    delay: Option<Delay>,
+    done: bool,
+}
+
+impl BodyStream {
+    pub(crate) fn new(body: Body, limit: Option<usize>) -> Self {
+        Self {
+            body,
+            limit,
+            received: 0,
+            timeout: None,
+            delay: None,
+            done: false,
+        }
+    }
+
+    /// Sets the limit of the whole body, the body limit of the context by
+    /// default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Removes the limit of the whole body.
+    pub fn unlimited(mut self) -> Self {
+        self.limit = None;
+        self
+    }
+
+    /// Sets how long to wait for each chunk, no timeout by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Deserializes one JSON value per line of at most `max_line` bytes.
+    pub(crate) fn ndjson<T: DeserializeOwned>(self, max_line: usize) -> NdJsonStream<T> {
+        NdJsonStream {
+            inner: self,
+            buf: BytesMut::new(),
+            scanned: 0,
+            max_line,
+            eof: false,
+            _t: PhantomData,
+        }
+    }
+
+    /// The bytes received so far.
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    fn fail(&mut self, rejection: Rejection) -> Poll<Option<Result<Bytes>>> {
+        self.done = true;
+        Poll::Ready(Some(Err(rejection.into())))
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut self.body).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                self.delay = None;
+                self.received += chunk.len();
+                if let Some(limit) = self.limit.filter(|limit| self.received > *limit) {
+                    return self.fail(Rejection::PayloadTooLarge(limit));
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(e))) => self.fail(Rejection::InvalidBody(e.to_string())),
+            Poll::Ready(None) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending => {
+                let timeout = match self.timeout {
+                    Some(timeout) => timeout,
+                    None => return Poll::Pending,
+                };
+                let delay = self.delay.get_or_insert_with(|| Delay::new(timeout));
+                ready!(Pin::new(delay).poll(cx));
+                self.fail(Rejection::BodyTimeout(timeout))
+            }
+        }
+    }
+}
+
+/// Newline delimited JSON records of the request body, see `Context::ndjson`.
+///
+/// Only the current line is buffered, up to the line limit. Blank lines are
+/// skipped, a record that fails to deserialize yields an error and the stream
+/// goes on with the next line. A line over the limit ends the stream with
+/// `Rejection::LineTooLong`.
+pub struct NdJsonStream<T> {
+    inner: BodyStream,
+    buf: BytesMut,
+    /// The bytes of `buf` known to hold no newline.
+    scanned: usize,
+    max_line: usize,
+    eof: bool,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> NdJsonStream<T> {
+    /// Sets the limit of a line, the body limit of the context by default.
+    pub fn max_line(mut self, max_line: usize) -> Self {
+        self.max_line = max_line;
+        self
+    }
+
+    /// Sets the limit of the whole body, there is none by default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.inner = self.inner.limit(limit);
+        self
+    }
+
+    /// Sets how long to wait for each chunk, no timeout by default.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.inner = self.inner.timeout(timeout);
+        self
+    }
+
+    /// The bytes received so far.
+    pub fn received(&self) -> usize {
+        self.inner.received()
+    }
+
+    fn next_line(&mut self) -> Result<Option<Bytes>> {
+        loop {
+            let newline = self.buf[self.scanned..]
+                .iter()
+                .position(|b| *b == b'\n')
+                .map(|i| self.scanned + i);
+            let line = match newline {
+                Some(i) if i > self.max_line => {
+                    return Err(Rejection::LineTooLong(self.max_line).into())
+                }
+                Some(i) => self.buf.split_to(i + 1).freeze(),
+                None if self.buf.len() > self.max_line => {
+                    return Err(Rejection::LineTooLong(self.max_line).into())
+                }
+                None if self.eof && !self.buf.is_empty() => self.buf.split().freeze(),
+                None => {
+                    self.scanned = self.buf.len();
+                    return Ok(None);
+                }
+            };
+            self.scanned = 0;
+            if line.iter().any(|b| !b.is_ascii_whitespace()) {
+                return Ok(Some(line));
+            }
+        }
+    }
+
+    fn fail(&mut self, e: std::io::Error) -> Poll<Option<Result<T>>> {
+        self.eof = true;
+        self.buf.clear();
+        self.scanned = 0;
+        Poll::Ready(Some(Err(e)))
+    }
+}
+
+impl<T: DeserializeOwned> Stream for NdJsonStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.next_line() {
+                Ok(Some(line)) => {
+                    return Poll::Ready(Some(
+                        serde_json::from_slice(&line).map_err(|e| Rejection::json(e).into()),
+                    ))
+                }
+                Ok(None) => {}
+                Err(e) => return self.fail(e),
+            }
+
+            if self.eof {
+                return Poll::Ready(None);
+            }
+
+            match ready!(Pin::new(&mut self.inner).poll_next(cx)) {
+                Some(Ok(chunk)) => self.buf.extend_from_slice(&chunk),
+                Some(Err(e)) => return self.fail(e),
+                None => self.eof = true,
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! In-process pub/sub for fanning events out to streaming clients.
+//!
+//! ```ignore
+//! // in the app state
+//! struct State {
+//!     events: Broadcast<Update>,
+//! }
+//!
+//! async fn subscribe(cx: Context<State>) -> impl IntoResponse {
+//!     let updates = cx.state().events.subscribe("dashboard");
+//!     Sse::new(updates.map(|update| Event::default().json(&update)))
+//! }
+//!
+//! async fn publish(mut cx: Context<State>) -> Result {
+//!     let update = cx.json().await?;
+//!     cx.state().events.publish("dashboard", update);
+//!     Ok(StatusCode::ACCEPTED.into_response())
+//! }
+//! ```
+
+use fnv::FnvHashMap;
+use futures::{
+    channel::mpsc::{self, Receiver, Sender},
+    stream::Stream,
+};
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+    task::{Context, Poll},
+};
+
+/// The default number of messages buffered per subscriber.
+pub const DEFAULT_CAPACITY: usize = 64;
+
+/// What happens to a subscriber whose buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LagPolicy {
+    /// The message is dropped for this subscriber only, see
+    /// `Subscription::lagged`.
+    Drop,
+    /// The subscriber is disconnected, its stream ends.
+    Disconnect,
+}
+
+impl Default for LagPolicy {
+    fn default() -> Self {
+        Self::Drop
+    }
+}
+
+/// Counters of a topic.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TopicStats {
+    /// Current subscribers.
+    pub subscribers: usize,
+    /// Messages published.
+    pub published: u64,
+    /// Messages dropped for lagging subscribers.
+    pub dropped: u64,
+    /// Subscribers disconnected for lagging.
+    pub disconnected: u64,
+}
+
+struct Subscriber<T> {
+    id: u64,
+    tx: Sender<T>,
+    lagged: Arc<AtomicU64>,
+}
+
+struct Hub<T> {
+    topics: FnvHashMap<String, Vec<Subscriber<T>>>,
+    stats: FnvHashMap<String, TopicStats>,
+    next_id: u64,
+}
+
+/// A hub of named topics, each delivering every published message to all of
+/// its subscribers.
+///
+/// Each subscriber has its own bounded buffer, so one slow client never
+/// holds up the others, it is handled by the `LagPolicy` instead. A topic is
+/// removed once its last subscriber is gone, its counters are kept until
+/// `reset_stats`.
+pub struct Broadcast<T> {
+    hub: Arc<Mutex<Hub<T>>>,
+    capacity: usize,
+    policy: LagPolicy,
+}
+
+impl<T: Clone + Send + 'static> Broadcast<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    /// Buffers up to `capacity` messages per subscriber, at least one.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            hub: Arc::new(Mutex::new(Hub {
+                topics: FnvHashMap::default(),
+                stats: FnvHashMap::default(),
+                next_id: 0,
+            })),
+            capacity,
+            policy: LagPolicy::default(),
+        }
+    }
+
+    pub fn lag_policy(mut self, policy: LagPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Subscribes to a topic, the subscription ends when it is dropped, for
+    /// example when the client of a streaming response disconnects.
+    pub fn subscribe(&self, topic: &str) -> Subscription<T> {
+        // A channel holds one message more than its buffer.
+        let (tx, rx) = mpsc::channel(self.capacity.saturating_sub(1));
+        let lagged = Arc::new(AtomicU64::new(0));
+
+        let mut hub = lock(&self.hub);
+        let id = hub.next_id;
+        hub.next_id += 1;
+        hub.stats.entry(topic.to_owned()).or_default();
+        hub.topics
+            .entry(topic.to_owned())
+            .or_default()
+            .push(Subscriber {
+                id,
+                tx,
+                lagged: lagged.clone(),
+            });
+
+        Subscription {
+            rx,
+            lagged,
+            topic: topic.to_owned(),
+            id,
+            hub: Arc::downgrade(&self.hub),
+        }
+    }
+
+    /// Sends a message to the subscribers of a topic, returns how many
+    /// received it.
+    pub fn publish(&self, topic: &str, msg: T) -> usize {
+        let mut hub = lock(&self.hub);
+        let hub = &mut *hub;
+        let subscribers = match hub.topics.get_mut(topic) {
+            Some(subscribers) => subscribers,
+            None => return 0,
+        };
+
+        let policy = self.policy;
+        let stats = hub.stats.entry(topic.to_owned()).or_default();
+        let mut delivered = 0;
+
+        stats.published += 1;
+        *subscribers = std::mem::replace(subscribers, Vec::new())
+            .into_iter()
+            .filter_map(|mut s| match s.tx.try_send(msg.clone()) {
+                Ok(()) => {
+                    delivered += 1;
+                    Some(s)
+                }
+                Err(e) if e.is_full() => match policy {
+                    LagPolicy::Drop => {
+                        s.lagged.fetch_add(1, Ordering::Relaxed);
+                        stats.dropped += 1;
+                        Some(s)
+                    }
+                    LagPolicy::Disconnect => {
+                        stats.disconnected += 1;
+                        None
+                    }
+                },
+                Err(_) => None,
+            })
+            .collect();
+
+        if subscribers.is_empty() {
+            hub.topics.remove(topic);
+        }
+
+        delivered
+    }
+
+    /// The number of subscribers of a topic.
+    pub fn subscribers(&self, topic: &str) -> usize {
+        lock(&self.hub).topics.get(topic).map_or(0, Vec::len)
+    }
+
+    /// The counters of a topic, `None` if it never had a subscriber or its
+    /// counters were reset.
+    pub fn stats(&self, topic: &str) -> Option<TopicStats> {
+        let hub = lock(&self.hub);
+        let stats = hub.stats.get(topic)?;
+        Some(TopicStats {
+            subscribers: hub.topics.get(topic).map_or(0, Vec::len),
+            ..*stats
+        })
+    }
+
+    /// Forgets the counters of a topic, they start again from zero.
+    pub fn reset_stats(&self, topic: &str) {
+        let mut hub = lock(&self.hub);
+        if hub.topics.contains_key(topic) {
+            hub.stats.insert(topic.to_owned(), TopicStats::default());
+        } else {
+            hub.stats.remove(topic);
+        }
+    }
+
+    /// The topics with subscribers.
+    pub fn topics(&self) -> Vec<String> {
+        lock(&self.hub).topics.keys().cloned().collect()
+    }
+}
+
+fn lock<T>(hub: &Mutex<Hub<T>>) -> MutexGuard<'_, Hub<T>> {
+    hub.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl<T: Clone + Send + 'static> Default for Broadcast<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for Broadcast<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broadcast")
+            .field("capacity", &self.capacity)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// The messages of a topic, see `Broadcast::subscribe`.
+pub struct Subscription<T> {
+    rx: Receiver<T>,
+    lagged: Arc<AtomicU64>,
+    topic: String,
+    id: u64,
+    hub: Weak<Mutex<Hub<T>>>,
+}
+
+impl<T> Subscription<T> {
+    /// The number of messages dropped because this subscriber lagged.
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Stream for Subscription<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
+
+/// Leaves the topic, which is removed with its last subscriber.
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        let hub = match self.hub.upgrade() {
+            Some(hub) => hub,
+            None => return,
+        };
+        let mut hub = lock(&hub);
+        if let Some(subscribers) = hub.topics.get_mut(&self.topic) {
+            subscribers.retain(|s| s.id != self.id);
+            if subscribers.is_empty() {
+                hub.topics.remove(&self.topic);
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Subscription")
+            .field("topic", &self.topic)
+            .field("lagged", &self.lagged())
+            .finish()
+    }
+}
@@ -1,27 +1,37 @@
-use bytes::Bytes;
-use futures::future::BoxFuture;
+use bytes::{Bytes, BytesMut};
+use futures::{channel::oneshot, future::BoxFuture, StreamExt};
 use hyper::{
-    header::{HeaderMap, HeaderValue},
-    http::Extensions,
+    header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_LENGTH, CONTENT_TYPE},
+    http::{request::Parts, Extensions},
     Body, Method, Uri, Version,
 };
-use std::{
-    fmt,
-    io::{ErrorKind, Result},
-    sync::Arc,
-};
+use std::{fmt, io::Result, sync::Arc};
 
 #[cfg(feature = "multipart")]
-use hyper::header::CONTENT_TYPE;
+use multipart_async::server::Multipart;
 #[cfg(feature = "multipart")]
-use std::io::Error;
+use std::io::{Error, ErrorKind};
+
+use crate::{
+    debug::RouteParams, negotiate, BodyStream, Middleware, NdJsonStream, Parameters, Rejection,
+    Request, Response,
+};
 
-use crate::{Middleware, Parameters, Request, Response};
+#[cfg(feature = "websocket")]
+use crate::websocket::WebSocketUpgrade;
+
+/// The default limit of a buffered request body, 2 MiB.
+pub const DEFAULT_BODY_LIMIT: usize = 2 * 1024 * 1024;
 
 /// The `Context` of the current HTTP request.
 pub struct Context<State> {
-    state: Arc<State>,
+    pub(crate) state: Arc<State>,
     request: Request,
+    buffered: Option<Bytes>,
+    body_taken: bool,
+    body_limit: usize,
+    strict_content_type: bool,
+    parts: Option<oneshot::Sender<Parts>>,
     pub params: Vec<(String, String)>,
     pub middleware: Vec<Arc<dyn Middleware<Self>>>,
 }
@@ -37,6 +47,11 @@ impl<State: Send + Sync + 'static> Context<State> {
         Self {
             state,
             request,
+            buffered: None,
+            body_taken: false,
+            body_limit: DEFAULT_BODY_LIMIT,
+            strict_content_type: true,
+            parts: None,
             params,
             middleware,
         }
@@ -116,6 +131,26 @@ impl<State: Send + Sync + 'static> Context<State> {
         self.request.extensions_mut()
     }
 
+    /// Returns the offered media type the `accept` header prefers, honouring
+    /// q-values and wildcards, or `None` when nothing offered is acceptable.
+    ///
+    /// ```ignore
+    /// match cx.accepts(&["application/json", "text/html"]) {
+    ///     Some("text/html") => html(page),
+    ///     Some(_) => json(&data),
+    ///     None => StatusCode::NOT_ACCEPTABLE.into_response(),
+    /// }
+    /// ```
+    pub fn accepts<'a>(&self, offers: &[&'a str]) -> Option<&'a str> {
+        let accept = self.headers().get(ACCEPT).and_then(|v| v.to_str().ok());
+        negotiate::best(accept, offers)
+    }
+
+    /// The `last-event-id` header sent by a reconnecting event stream client.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.header("last-event-id").and_then(|v| v.to_str().ok())
+    }
+
     /// Access the request's path.
     pub fn path(&self) -> &str {
         self.uri().path()
@@ -126,58 +161,304 @@ impl<State: Send + Sync + 'static> Context<State> {
         self.uri().query().unwrap_or("")
     }
 
-    /// From `?query=string`.
+    /// From `?query=string`, fails with `Rejection::InvalidQuery`.
     /// TODO: check 'string-length'
     pub fn query<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
         let query = self.query_string();
-        Ok(serde_qs::from_str(query).map_err(|_| ErrorKind::InvalidData)?)
+        Ok(serde_qs::from_str(query).map_err(|e| Rejection::InvalidQuery(e.to_string()))?)
     }
 
-    // Access a mutable request's body.
-    pub fn take_body(self) -> Body {
-        self.request.into_body()
+    /// Takes the request's body, leaving an empty body behind.
+    /// If the body was buffered, returns a replay of it, otherwise fails with
+    /// `Rejection::BodyTaken` when the body was already taken.
+    pub fn take_body(&mut self) -> Result<Body> {
+        if let Some(bytes) = &self.buffered {
+            return Ok(Body::from(bytes.clone()));
+        }
+        if self.body_taken {
+            return Err(Rejection::BodyTaken.into());
+        }
+        self.body_taken = true;
+        Ok(std::mem::replace(self.request.body_mut(), Body::empty()))
     }
 
-    /// Todo: limit size
-    /// https://github.com/actix/actix-web/blob/master/src/types/form.rs#L332
-    /// https://github.com/stream-utils/raw-body
-    /// https://github.com/rustasync/http-service/blob/master/src/lib.rs#L96
-    /// https://github.com/seanmonstar/warp/blob/master/src/filters/body.rs
-    pub async fn bytes(self) -> Result<Bytes> {
-        let body = hyper::body::to_bytes(self.take_body())
-            .await
-            .map_err(|_| ErrorKind::InvalidData)?;
-        Ok(body)
+    /// Reads the whole body and keeps it, so it can be read again here and
+    /// by the following middleware and handler.
+    pub async fn buffer_body(&mut self) -> Result<Bytes> {
+        let bytes = self.bytes().await?;
+        *self.request.body_mut() = Body::from(bytes.clone());
+        self.buffered = Some(bytes.clone());
+        Ok(bytes)
     }
 
-    /// From `application/json`
-    /// TODO: check `content-type` and 'content-length'
-    pub async fn json<T: serde::de::DeserializeOwned>(self) -> Result<T> {
-        let body = self.bytes().await?;
-        Ok(serde_json::from_slice(&body).map_err(|_| ErrorKind::InvalidData)?)
+    /// The limit of the body read by `bytes`, `json`, `string`, `form` and
+    /// the other body parsers, each has a `_with_limit` variant to override
+    /// it for one call.
+    pub fn body_limit(&self) -> usize {
+        self.body_limit
     }
 
-    pub async fn string(self) -> Result<String> {
-        let body = self.bytes().await?;
-        Ok(String::from_utf8(body.to_vec()).map_err(|_| ErrorKind::InvalidData)?)
+    /// Overrides the body limit for this request.
+    pub fn set_body_limit(&mut self, limit: usize) {
+        self.body_limit = limit;
+    }
+
+    /// Streams the body chunk by chunk instead of buffering it, with the body
+    /// limit as a running limit.
+    ///
+    /// ```ignore
+    /// let mut body = cx.body_stream()?.limit(4 << 30).timeout(Duration::from_secs(30));
+    /// while let Some(chunk) = body.next().await {
+    ///     file.write_all(&chunk?).await?;
+    /// }
+    /// ```
+    pub fn body_stream(&mut self) -> Result<BodyStream> {
+        Ok(BodyStream::new(self.take_body()?, Some(self.body_limit)))
+    }
+
+    /// Streams `application/x-ndjson` records, one JSON value per line.
+    ///
+    /// The body limit applies to each line, the whole body has no limit
+    /// unless one is set on the stream.
+    ///
+    /// ```ignore
+    /// let mut records = cx.ndjson::<Record>()?.max_line(64 * 1024).limit(4 << 30);
+    /// while let Some(record) = records.next().await {
+    ///     db.insert(record?).await?;
+    /// }
+    /// ```
+    pub fn ndjson<T: serde::de::DeserializeOwned>(&mut self) -> Result<NdJsonStream<T>> {
+        self.expect_content_type("application/x-ndjson", |essence| {
+            essence == "application/x-ndjson" || essence == "application/ndjson"
+        })?;
+        let max_line = self.body_limit;
+        Ok(BodyStream::new(self.take_body()?, None).ndjson(max_line))
+    }
+
+    /// Validates a WebSocket upgrade request and takes the body, which
+    /// resolves the upgraded connection, see `WebSocketUpgrade::upgrade`.
+    #[cfg(feature = "websocket")]
+    pub fn websocket(&mut self) -> Result<WebSocketUpgrade> {
+        let mut upgrade = WebSocketUpgrade::new(self.method(), self.headers())?;
+        upgrade.body = self.take_body()?;
+        Ok(upgrade)
+    }
+
+    /// Reads the whole body, up to the body limit.
+    pub async fn bytes(&mut self) -> Result<Bytes> {
+        self.bytes_with_limit(self.body_limit).await
+    }
+
+    /// Reads the whole body, up to `limit` bytes.
+    ///
+    /// Fails with `Rejection::PayloadTooLarge` as soon as `content-length`
+    /// or the bytes received exceed the limit.
+    pub async fn bytes_with_limit(&mut self, limit: usize) -> Result<Bytes> {
+        if let Some(bytes) = &self.buffered {
+            if bytes.len() > limit {
+                return Err(Rejection::PayloadTooLarge(limit).into());
+            }
+            return Ok(bytes.clone());
+        }
+
+        let len = self.content_length(limit)?;
+        let mut body = self.take_body()?;
+        let mut buf = BytesMut::with_capacity(len.unwrap_or(0));
+
+        while let Some(chunk) = body.next().await {
+            let chunk = chunk.map_err(|e| Rejection::InvalidBody(e.to_string()))?;
+            if buf.len() + chunk.len() > limit {
+                return Err(Rejection::PayloadTooLarge(limit).into());
+            }
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(buf.freeze())
+    }
+
+    /// The `content-length`, failing with `Rejection::PayloadTooLarge` when
+    /// it exceeds `limit`.
+    fn content_length(&self, limit: usize) -> Result<Option<usize>> {
+        let len = self
+            .header(CONTENT_LENGTH.as_str())
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        if len.map_or(false, |len| len > limit) {
+            return Err(Rejection::PayloadTooLarge(limit).into());
+        }
+
+        Ok(len)
+    }
+
+    /// Whether the body parsers check `content-type`, on by default.
+    /// Turn it off for legacy clients which send a wrong or no `content-type`.
+    pub fn set_strict_content_type(&mut self, strict: bool) {
+        self.strict_content_type = strict;
+    }
+
+    /// From `application/json` or `application/*+json`.
+    pub async fn json<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        self.json_with_limit(self.body_limit).await
+    }
+
+    /// Like `json`, up to `limit` bytes.
+    pub async fn json_with_limit<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T> {
+        self.expect_json()?;
+        let body = self.bytes_with_limit(limit).await?;
+        Ok(serde_json::from_slice(&body).map_err(Rejection::json)?)
+    }
+
+    pub async fn string(&mut self) -> Result<String> {
+        self.string_with_limit(self.body_limit).await
+    }
+
+    /// Like `string`, up to `limit` bytes.
+    pub async fn string_with_limit(&mut self, limit: usize) -> Result<String> {
+        let body = self.bytes_with_limit(limit).await?;
+        Ok(String::from_utf8(body.to_vec()).map_err(|e| Rejection::InvalidBody(e.to_string()))?)
     }
 
     /// From `application/x-www-form-urlencoded`
-    /// TODO: check `content-type` and 'content-length'
-    pub async fn form<T: serde::de::DeserializeOwned>(self) -> Result<T> {
-        let body = self.bytes().await?;
-        Ok(serde_urlencoded::from_bytes(&body).map_err(|_| ErrorKind::InvalidData)?)
+    pub async fn form<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        self.form_with_limit(self.body_limit).await
+    }
+
+    /// Like `form`, up to `limit` bytes.
+    pub async fn form_with_limit<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T> {
+        self.expect_form()?;
+        let body = self.bytes_with_limit(limit).await?;
+        Ok(serde_urlencoded::from_bytes(&body)
+            .map_err(|e| Rejection::InvalidForm(e.to_string()))?)
+    }
+
+    fn expect_json(&self) -> Result<()> {
+        self.expect_content_type("application/json", |essence| {
+            essence == "application/json"
+                || (essence.starts_with("application/") && essence.ends_with("+json"))
+        })
+    }
+
+    fn expect_form(&self) -> Result<()> {
+        self.expect_content_type("application/x-www-form-urlencoded", |essence| {
+            essence == "application/x-www-form-urlencoded"
+        })
+    }
+
+    /// From `application/msgpack`
+    #[cfg(feature = "msgpack")]
+    pub async fn msgpack<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        self.msgpack_with_limit(self.body_limit).await
+    }
+
+    /// Like `msgpack`, up to `limit` bytes.
+    #[cfg(feature = "msgpack")]
+    pub async fn msgpack_with_limit<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T> {
+        self.expect_content_type("application/msgpack", |essence| {
+            essence == "application/msgpack"
+                || essence == "application/x-msgpack"
+                || essence == "application/vnd.msgpack"
+        })?;
+        let body = self.bytes_with_limit(limit).await?;
+        Ok(
+            rmp_serde::from_slice(&body).map_err(|e| Rejection::InvalidFormat {
+                format: "MessagePack",
+                message: e.to_string(),
+            })?,
+        )
+    }
+
+    /// From `application/cbor`
+    #[cfg(feature = "cbor")]
+    pub async fn cbor<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        self.cbor_with_limit(self.body_limit).await
+    }
+
+    /// Like `cbor`, up to `limit` bytes.
+    #[cfg(feature = "cbor")]
+    pub async fn cbor_with_limit<T: serde::de::DeserializeOwned>(
+        &mut self,
+        limit: usize,
+    ) -> Result<T> {
+        self.expect_content_type("application/cbor", |essence| {
+            essence == "application/cbor"
+                || (essence.starts_with("application/") && essence.ends_with("+cbor"))
+        })?;
+        let body = self.bytes_with_limit(limit).await?;
+        Ok(
+            serde_cbor::from_slice(&body).map_err(|e| Rejection::InvalidFormat {
+                format: "CBOR",
+                message: e.to_string(),
+            })?,
+        )
+    }
+
+    /// Fails with `Rejection::UnsupportedMediaType` unless the media type of
+    /// `content-type` matches and its charset, if any, is UTF-8.
+    fn expect_content_type(
+        &self,
+        expected: &'static str,
+        matches: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        if !self.strict_content_type {
+            return Ok(());
+        }
+
+        let supported = self
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map_or(false, |v| {
+                let mut parts = v.split(';');
+                let essence = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+                matches(&essence)
+                    && parts.all(|param| {
+                        let mut kv = param.splitn(2, '=');
+                        match (kv.next(), kv.next()) {
+                            (Some(k), Some(v)) if k.trim().eq_ignore_ascii_case("charset") => {
+                                let v = v.trim().trim_matches('"');
+                                v.eq_ignore_ascii_case("utf-8") || v.eq_ignore_ascii_case("utf8")
+                            }
+                            _ => true,
+                        }
+                    })
+            });
+
+        if supported {
+            Ok(())
+        } else {
+            Err(Rejection::UnsupportedMediaType(expected).into())
+        }
     }
 
     /// https://github.com/expressjs/multer
     /// https://crates.io/crates/multipart
     /// https://github.com/abonander/multipart-async
-    /// From `multipart/form-data`
-    /// TODO: check `content-type` and 'content-length'
+    /// From `multipart/form-data`, up to the body limit. Fails with
+    /// `Rejection::UnsupportedMediaType` for another `content-type`.
+    #[cfg(feature = "multipart")]
+    pub fn multipart(&mut self) -> Result<Multipart<BodyStream>> {
+        self.multipart_with_limit(self.body_limit)
+    }
+
+    /// Like `multipart`, up to `limit` bytes for the whole body.
     #[cfg(feature = "multipart")]
-    pub fn multipart(&mut self) -> Result<Multipart<&mut Body>> {
+    pub fn multipart_with_limit(&mut self, limit: usize) -> Result<Multipart<BodyStream>> {
         const BOUNDARY: &str = "boundary=";
 
+        self.expect_content_type("multipart/form-data", |essence| {
+            essence == "multipart/form-data"
+        })?;
+
         let boundary = self
             .headers()
             .get(CONTENT_TYPE)
@@ -188,21 +469,34 @@ impl<State: Send + Sync + 'static> Context<State> {
             })
             .ok_or_else(|| Error::new(ErrorKind::Other, "no boundary found"))?;
 
-        Ok(multipart_async::server::Multipart::with_body(
-            self.take_body(),
+        self.content_length(limit)?;
+
+        Ok(Multipart::with_body(
+            self.body_stream()?.limit(limit),
             boundary,
         ))
     }
 
+    /// From the route params, fails with `Rejection::MissingParam` or
+    /// `Rejection::InvalidParam`.
     pub fn params<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
         Ok(Parameters::from_vec_string(&self.params)
             .parse()
-            .map_err(|_| ErrorKind::InvalidData)?)
+            .map_err(Rejection::params)?)
     }
 
     // generate url
     // pub fn url_for(&self) {}
 
+    /// Hands the request parts over when the context is dropped, with the
+    /// route params as a `RouteParams` extension. Nothing is copied, the
+    /// parts are the ones the middleware and handlers left.
+    pub fn parts_on_drop(&mut self) -> oneshot::Receiver<Parts> {
+        let (tx, rx) = oneshot::channel();
+        self.parts = Some(tx);
+        rx
+    }
+
     /// Next middleare
     pub fn next<'a>(mut self) -> BoxFuture<'a, Response> {
         if self.middleware.is_empty() {
@@ -213,6 +507,19 @@ impl<State: Send + Sync + 'static> Context<State> {
     }
 }
 
+impl<State> Drop for Context<State> {
+    fn drop(&mut self) {
+        if let Some(tx) = self.parts.take() {
+            let (mut parts, _) =
+                std::mem::replace(&mut self.request, Request::new(Body::empty())).into_parts();
+            parts
+                .extensions
+                .insert(RouteParams(std::mem::replace(&mut self.params, Vec::new())));
+            let _ = tx.send(parts);
+        }
+    }
+}
+
 impl<State> fmt::Debug for Context<State> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Context").finish()
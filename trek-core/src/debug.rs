@@ -0,0 +1,127 @@
+//! Development error pages.
+//!
+//! `debug_handler` renders errors as an HTML page with the error, its
+//! sources, the backtrace of server errors when the `backtrace` feature is
+//! on, and the request. The page is only rendered in debug builds, release
+//! builds render the plain `ErrorResponse` instead, so it never reaches
+//! production.
+//!
+//! ```ignore
+//! app.error_handler(debug_handler);
+//! ```
+
+use http::request::Parts;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use std::{error::Error as _, fmt::Write};
+
+use crate::{Error, Response};
+
+/// The route params of the request, kept in the request parts handed to the
+/// error handler.
+#[derive(Debug, Clone, Default)]
+pub struct RouteParams(pub Vec<(String, String)>);
+
+/// An error handler rendering the debug page in debug builds.
+pub fn debug_handler(err: Error, parts: &Parts) -> Response {
+    debug_page(&err, parts)
+}
+
+/// Renders the debug page of an error, with the status of its response. In
+/// release builds, renders the `ErrorResponse` of the error instead.
+pub fn debug_page(err: &Error, parts: &Parts) -> Response {
+    let res = err.as_error_response().error_response();
+    if !cfg!(debug_assertions) {
+        return res;
+    }
+
+    let status = res.status();
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{status}</title>\
+         <style>body{{font-family:sans-serif;margin:2em}}pre{{background:#f5f5f5;padding:1em;\
+         overflow:auto}}td{{padding:.2em 1em .2em 0;vertical-align:top;font-family:monospace}}\
+         </style></head><body><h1>{status}</h1><pre>{error}</pre>",
+        status = status,
+        error = escape(&err.to_string()),
+    );
+
+    let mut source = err.source();
+    if source.is_some() {
+        html.push_str("<h2>Caused by</h2><ol>");
+        while let Some(e) = source {
+            let _ = write!(html, "<li><pre>{}</pre></li>", escape(&e.to_string()));
+            source = e.source();
+        }
+        html.push_str("</ol>");
+    }
+
+    #[cfg(feature = "backtrace")]
+    {
+        if let Some(backtrace) = err.backtrace() {
+            let mut backtrace = backtrace.clone();
+            backtrace.resolve();
+            let _ = write!(
+                html,
+                "<h2>Backtrace</h2><pre>{}</pre>",
+                escape(&format!("{:?}", backtrace))
+            );
+        }
+    }
+
+    html.push_str("<h2>Request</h2><table>");
+    row(&mut html, "method", parts.method.as_str());
+    row(&mut html, "uri", &parts.uri.to_string());
+    row(&mut html, "version", &format!("{:?}", parts.version));
+    html.push_str("</table>");
+
+    if let Some(RouteParams(params)) = parts.extensions.get::<RouteParams>() {
+        html.push_str("<h2>Route params</h2><table>");
+        for (name, value) in params {
+            row(&mut html, name, value);
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h2>Headers</h2><table>");
+    for (name, value) in &parts.headers {
+        let value = match name.as_str() {
+            "authorization" | "proxy-authorization" | "cookie" => "[hidden]",
+            _ => value.to_str().unwrap_or("[binary]"),
+        };
+        row(&mut html, name.as_str(), value);
+    }
+    html.push_str("</table></body></html>");
+
+    let mut res = Response::new(html.into());
+    *res.status_mut() = status;
+    res.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    res
+}
+
+fn row(html: &mut String, name: &str, value: &str) {
+    html.push_str("<tr><td>");
+    html.push_str(&escape(name));
+    html.push_str("</td><td>");
+    html.push_str(&escape(value));
+    html.push_str("</td></tr>");
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
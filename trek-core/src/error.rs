@@ -1,4 +1,4 @@
-use crate::{helpers::Writer, IntoResponse, Response};
+use crate::{helpers::Writer, IntoResponse, Rejection, Response};
 use bytes::BytesMut;
 use hyper::{header, Body, StatusCode};
 use std::{
@@ -11,21 +11,51 @@ pub type Result<T = Response, E = Error> = result::Result<T, E>;
 
 pub struct Error {
     e: Box<dyn ErrorResponse + Send + Sync>,
+    #[cfg(feature = "backtrace")]
+    backtrace: Option<backtrace_rs::Backtrace>,
 }
 
 impl Error {
     pub fn new(e: Box<dyn ErrorResponse + Send + Sync>) -> Self {
-        Self { e }
+        Self {
+            #[cfg(feature = "backtrace")]
+            backtrace: capture_backtrace(e.as_ref()),
+            e,
+        }
     }
 
     pub fn as_error_response(&self) -> &dyn ErrorResponse {
         self.e.as_ref()
     }
+
+    /// Where the error was created, captured for server errors in debug
+    /// builds. Symbols are not resolved yet.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> Option<&backtrace_rs::Backtrace> {
+        self.backtrace.as_ref()
+    }
+}
+
+/// Client errors are expected, only server errors are worth the cost of a
+/// backtrace.
+#[cfg(feature = "backtrace")]
+fn capture_backtrace(e: &dyn ErrorResponse) -> Option<backtrace_rs::Backtrace> {
+    if cfg!(debug_assertions) && e.status().is_server_error() {
+        Some(backtrace_rs::Backtrace::new_unresolved())
+    } else {
+        None
+    }
 }
 
 pub trait ErrorResponse: error::Error + Send + Sync {
+    /// The status of `error_response`, without building the response. Keep
+    /// it in sync when overriding `error_response`.
+    fn status(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+
     fn error_response(&self) -> Response {
-        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        self.status().into_response()
     }
 
     fn render_response(&self) -> Response {
@@ -53,7 +83,11 @@ impl fmt::Debug for Error {
     }
 }
 
-impl error::Error for Error {}
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.e.source()
+    }
+}
 
 /// `Error` for any error that implements `ErrorResponse`
 impl<T: ErrorResponse + error::Error + 'static> From<T> for Error {
@@ -62,21 +96,39 @@ impl<T: ErrorResponse + error::Error + 'static> From<T> for Error {
     }
 }
 
-/// Return `InternalServerError` for `io::Error`
+/// Return the status of a carried `Rejection`, `BadRequest` for invalid data
+/// and `InternalServerError` for other `io::Error`s
 impl ErrorResponse for io::Error {
-    fn error_response(&self) -> Response {
+    fn status(&self) -> StatusCode {
+        if let Some(rejection) = rejection(self) {
+            return rejection.status();
+        }
         match self.kind() {
             io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
             io::ErrorKind::PermissionDenied => StatusCode::FORBIDDEN,
+            io::ErrorKind::InvalidData => StatusCode::BAD_REQUEST,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
-        .into_response()
     }
+
+    fn error_response(&self) -> Response {
+        match rejection(self) {
+            Some(rejection) => rejection.error_response(),
+            None => self.status().into_response(),
+        }
+    }
+}
+
+fn rejection(e: &io::Error) -> Option<&Rejection> {
+    e.get_ref().and_then(|e| e.downcast_ref::<Rejection>())
 }
 
+/// Renders `error_response` and keeps the error in the response extensions,
+/// so the app's error handler can render and log it with the request.
 impl IntoResponse for Error {
     fn into_response(self) -> Response {
-        dbg!(&self.as_error_response());
-        self.as_error_response().error_response()
+        let mut res = self.as_error_response().error_response();
+        res.extensions_mut().insert(self);
+        res
     }
 }
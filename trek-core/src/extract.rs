@@ -0,0 +1,267 @@
+//! Typed extractors for handler arguments.
+//!
+//! ```ignore
+//! use trek::extract::{with, Json, Path, Query};
+//!
+//! router.post(
+//!     "/users/:id",
+//!     with(|Path(id): Path<u64>, Query(q): Query<Filter>, Json(user): Json<User>| {
+//!         async move { json(&(id, q, user)) }
+//!     }),
+//! );
+//! ```
+
+use futures::future::{BoxFuture, Future};
+use hyper::header::HeaderMap;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{any::type_name, marker::PhantomData, ops::Deref, sync::Arc};
+
+use crate::{Context, Handler, IntoResponse, Rejection, Response, Result};
+
+/// Types that can be created from the request context.
+pub trait FromContext<S>: Sized {
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>>;
+}
+
+/// Route params, from `:name` segments.
+#[derive(Debug, Clone)]
+pub struct Path<T>(pub T);
+
+impl<S, T> FromContext<S> for Path<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        let result = cx.params().map(Path).map_err(|e| Rejection::from(e).into());
+        Box::pin(async move { result })
+    }
+}
+
+/// From `?query=string`.
+#[derive(Debug, Clone)]
+pub struct Query<T>(pub T);
+
+impl<S, T> FromContext<S> for Query<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        let result = cx.query().map(Query).map_err(|e| Rejection::from(e).into());
+        Box::pin(async move { result })
+    }
+}
+
+/// From an `application/json` body. Also responds with JSON.
+#[derive(Debug, Clone)]
+pub struct Json<T>(pub T);
+
+impl<S, T> FromContext<S> for Json<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        Box::pin(async move {
+            cx.json()
+                .await
+                .map(Json)
+                .map_err(|e| Rejection::from(e).into())
+        })
+    }
+}
+
+impl<T: Serialize + Send> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        crate::json(&self.0)
+    }
+}
+
+/// From an `application/msgpack` body. Also responds with MessagePack.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone)]
+pub struct MsgPack<T>(pub T);
+
+#[cfg(feature = "msgpack")]
+impl<S, T> FromContext<S> for MsgPack<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        Box::pin(async move {
+            cx.msgpack()
+                .await
+                .map(MsgPack)
+                .map_err(|e| Rejection::from(e).into())
+        })
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl<T: Serialize + Send> IntoResponse for MsgPack<T> {
+    fn into_response(self) -> Response {
+        crate::msgpack(&self.0)
+    }
+}
+
+/// From an `application/cbor` body. Also responds with CBOR.
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone)]
+pub struct Cbor<T>(pub T);
+
+#[cfg(feature = "cbor")]
+impl<S, T> FromContext<S> for Cbor<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        Box::pin(async move {
+            cx.cbor()
+                .await
+                .map(Cbor)
+                .map_err(|e| Rejection::from(e).into())
+        })
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl<T: Serialize + Send> IntoResponse for Cbor<T> {
+    fn into_response(self) -> Response {
+        crate::cbor(&self.0)
+    }
+}
+
+/// From an `application/x-www-form-urlencoded` body.
+#[derive(Debug, Clone)]
+pub struct Form<T>(pub T);
+
+impl<S, T> FromContext<S> for Form<T>
+where
+    S: Send + Sync + 'static,
+    T: DeserializeOwned + Send + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        Box::pin(async move {
+            cx.form()
+                .await
+                .map(Form)
+                .map_err(|e| Rejection::from(e).into())
+        })
+    }
+}
+
+/// The app-state.
+#[derive(Debug)]
+pub struct State<S>(pub Arc<S>);
+
+impl<S> Clone for State<S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<S> Deref for State<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S: Send + Sync + 'static> FromContext<S> for State<S> {
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        let state = State(cx.state.clone());
+        Box::pin(async move { Ok(state) })
+    }
+}
+
+/// A local-state value, set with `Context::set`.
+#[derive(Debug, Clone)]
+pub struct Extension<T>(pub T);
+
+impl<S, T> FromContext<S> for Extension<T>
+where
+    S: Send + Sync + 'static,
+    T: Clone + Send + Sync + 'static,
+{
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        let result = cx
+            .get::<T>()
+            .cloned()
+            .map(Extension)
+            .ok_or_else(|| Rejection::MissingExtension(type_name::<T>()).into());
+        Box::pin(async move { result })
+    }
+}
+
+impl<S: Send + Sync + 'static> FromContext<S> for HeaderMap {
+    fn from_context(cx: &mut Context<S>) -> BoxFuture<'_, Result<Self>> {
+        let headers = cx.headers().clone();
+        Box::pin(async move { Ok(headers) })
+    }
+}
+
+/// A handler whose arguments are extracted from the context.
+pub struct Extract<F, T> {
+    f: F,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<F: Clone, T> Clone for Extract<F, T> {
+    fn clone(&self) -> Self {
+        Self {
+            f: self.f.clone(),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<F, T> std::fmt::Debug for Extract<F, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extract").finish()
+    }
+}
+
+/// Wraps a function taking extractors into a handler.
+pub fn with<F, T>(f: F) -> Extract<F, T> {
+    Extract { f, _t: PhantomData }
+}
+
+macro_rules! impl_handler {
+    ($($ty:ident),+) => {
+        impl<S, F, Fut, $($ty,)+> Handler<Context<S>> for Extract<F, ($($ty,)+)>
+        where
+            S: Send + Sync + 'static,
+            F: Clone + Send + Sync + 'static + Fn($($ty),+) -> Fut,
+            Fut: Future + Send + 'static,
+            Fut::Output: IntoResponse + Send + 'static,
+            $($ty: FromContext<S> + Send + 'static,)+
+        {
+            type Fut = BoxFuture<'static, Response>;
+
+            #[allow(non_snake_case)]
+            fn call(&self, mut cx: Context<S>) -> Self::Fut {
+                let f = self.f.clone();
+                Box::pin(async move {
+                    $(
+                        let $ty = match $ty::from_context(&mut cx).await {
+                            Ok(v) => v,
+                            Err(e) => return e.into_response(),
+                        };
+                    )+
+                    f($($ty),+).await.into_response()
+                })
+            }
+        }
+    };
+}
+
+impl_handler!(A);
+impl_handler!(A, B);
+impl_handler!(A, B, C);
+impl_handler!(A, B, C, D);
+impl_handler!(A, B, C, D, E);
+impl_handler!(A, B, C, D, E, G);
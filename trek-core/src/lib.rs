@@ -1,16 +1,38 @@
+mod body;
+pub mod broadcast;
 mod context;
+pub mod debug;
 mod error;
+pub mod extract;
 mod handler;
 pub mod helpers;
+pub mod long_poll;
 mod middleware;
+mod negotiate;
 mod parameters;
+mod problem;
+mod rejection;
 mod request;
 mod response;
+pub mod sse;
+pub mod stream;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
-pub use context::Context;
+pub use body::{BodyStream, NdJsonStream};
+pub use broadcast::Broadcast;
+pub use context::{Context, DEFAULT_BODY_LIMIT};
 pub use error::{Error, ErrorResponse, Result};
 pub use handler::{box_dyn_handler_into_middleware, into_box_dyn_handler, BoxDynHandler, Handler};
 pub use middleware::Middleware;
+pub use negotiate::Negotiate;
 pub use parameters::Parameters;
+pub use problem::{problem_handler, Problem};
+pub use rejection::Rejection;
 pub use request::Request;
 pub use response::{html, json, Body, IntoResponse, Response, StatusCode};
+
+#[cfg(feature = "cbor")]
+pub use response::cbor;
+#[cfg(feature = "msgpack")]
+pub use response::msgpack;
@@ -0,0 +1,203 @@
+//! Long polling.
+//!
+//! Clients send the version they have seen as `?since=`, the response is
+//! held until a newer version exists or the timeout passes.
+//!
+//! ```ignore
+//! struct State {
+//!     changes: Changes,
+//!     todos: RwLock<Vec<Todo>>,
+//! }
+//!
+//! async fn poll(cx: Context<State>) -> Result {
+//!     let state = cx.state();
+//!     long_poll(&cx, &state.changes, Duration::from_secs(30), |_| {
+//!         state.todos.read().unwrap().clone()
+//!     })
+//!     .await
+//! }
+//!
+//! async fn add(mut cx: Context<State>) -> Result {
+//!     let todo = cx.json().await?;
+//!     cx.state().todos.write().unwrap().push(todo);
+//!     cx.state().changes.bump();
+//!     Ok(StatusCode::CREATED.into_response())
+//! }
+//! ```
+
+use fnv::FnvHashMap;
+use futures::future::{self, Either, Future};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+    task::{Context as TaskContext, Poll, Waker},
+    time::Duration,
+};
+
+use crate::{json, Context, IntoResponse, Response, Result, StatusCode};
+
+/// A version counter that wakes the tasks waiting for a newer version.
+/// Store it in the app state next to the data it tracks.
+pub struct Changes {
+    version: AtomicU64,
+    waiters: Mutex<Waiters>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    next: usize,
+    wakers: FnvHashMap<usize, Waker>,
+}
+
+impl Changes {
+    pub fn new() -> Self {
+        Self {
+            version: AtomicU64::new(0),
+            waiters: Mutex::new(Waiters::default()),
+        }
+    }
+
+    /// The current version.
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Records a change, returns the new version.
+    pub fn bump(&self) -> u64 {
+        let version = self.version.fetch_add(1, Ordering::SeqCst) + 1;
+        for (_, waker) in self.lock().wakers.drain() {
+            waker.wake();
+        }
+        version
+    }
+
+    /// Resolves to the current version once it is newer than `since`, and
+    /// at once when `since` is ahead of it, for example a cursor from before
+    /// a restart reset the version.
+    pub fn changed(&self, since: u64) -> Changed<'_> {
+        Changed {
+            changes: self,
+            since,
+            key: None,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Waiters> {
+        self.waiters.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Changes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Changes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changes")
+            .field("version", &self.version())
+            .finish()
+    }
+}
+
+/// The future of `Changes::changed`. Dropping it, for example when the
+/// client disconnects, removes its waker.
+pub struct Changed<'a> {
+    changes: &'a Changes,
+    since: u64,
+    key: Option<usize>,
+}
+
+impl Future for Changed<'_> {
+    type Output = u64;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<u64> {
+        let version = self.changes.version();
+        if version != self.since {
+            return Poll::Ready(version);
+        }
+
+        let mut waiters = self.changes.lock();
+        // Checked again with the lock held, so a bump in between is not lost.
+        let version = self.changes.version();
+        if version != self.since {
+            return Poll::Ready(version);
+        }
+        let key = match self.key {
+            Some(key) => key,
+            None => {
+                waiters.next = waiters.next.wrapping_add(1);
+                waiters.next
+            }
+        };
+        waiters.wakers.insert(key, cx.waker().clone());
+        drop(waiters);
+        self.key = Some(key);
+        Poll::Pending
+    }
+}
+
+impl Drop for Changed<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.changes.lock().wakers.remove(&key);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Cursor {
+    since: Option<u64>,
+}
+
+/// The body of a long poll response, `version` is the cursor of the next
+/// poll.
+#[derive(Debug, Serialize)]
+pub struct Update<T> {
+    pub version: u64,
+    pub data: T,
+}
+
+/// Waits until the version is newer than `?since=`, then responds `200` with
+/// the version and the data loaded for it. Responds `204` when the timeout
+/// passes first. Responds at once with the current version when `since` is
+/// missing or ahead of it.
+pub async fn long_poll<S, T, F>(
+    cx: &Context<S>,
+    changes: &Changes,
+    timeout: Duration,
+    load: F,
+) -> Result
+where
+    S: Send + Sync + 'static,
+    T: Serialize,
+    F: FnOnce(u64) -> T,
+{
+    let Cursor { since } = cx.query()?;
+
+    let version = match since {
+        None => changes.version(),
+        Some(since) => match future::select(changes.changed(since), Delay::new(timeout)).await {
+            Either::Left((version, _)) => version,
+            Either::Right(_) => return Ok(StatusCode::NO_CONTENT.into_response()),
+        },
+    };
+
+    Ok(json(&Update {
+        version,
+        data: load(version),
+    }))
+}
+
+impl<T: Serialize + Send> IntoResponse for Update<T> {
+    fn into_response(self) -> Response {
+        json(&self)
+    }
+}
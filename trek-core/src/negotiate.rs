@@ -0,0 +1,178 @@
+//! Content negotiation with the `accept` header.
+
+use hyper::header::{HeaderValue, ACCEPT, CONTENT_TYPE, VARY};
+
+use crate::{json, Body, Context, IntoResponse, Response, StatusCode};
+
+/// Picks the offered media type the `accept` header prefers.
+///
+/// Each offer gets the q-value of the most specific range matching it, ties
+/// are won by the earlier offer. Offers with `q=0` are not acceptable, no
+/// `accept` header accepts the first offer.
+pub(crate) fn best<'a>(accept: Option<&str>, offers: &[&'a str]) -> Option<&'a str> {
+    let accept = match accept.map(str::trim) {
+        Some(accept) if !accept.is_empty() => accept,
+        _ => return offers.first().copied(),
+    };
+
+    let ranges = accept
+        .split(',')
+        .filter_map(parse_range)
+        .collect::<Vec<_>>();
+
+    let mut best: Option<(&str, f32)> = None;
+    for offer in offers {
+        let essence = offer.split(';').next().unwrap_or("").trim();
+        let q = ranges
+            .iter()
+            .filter_map(|(range, q)| specificity(range, essence).map(|s| (s, *q)))
+            .max_by_key(|(s, _)| *s)
+            .map_or(0.0, |(_, q)| q);
+        if q > 0.0 && best.map_or(true, |(_, b)| q > b) {
+            best = Some((offer, q));
+        }
+    }
+    best.map(|(offer, _)| offer)
+}
+
+/// `text/html;level=1;q=0.5` -> (`text/html`, 0.5)
+fn parse_range(range: &str) -> Option<(String, f32)> {
+    let mut parts = range.split(';');
+    let media = parts.next()?.trim().to_ascii_lowercase();
+    if media.is_empty() {
+        return None;
+    }
+    let mut q = 1.0;
+    for param in parts {
+        let mut kv = param.splitn(2, '=');
+        if let (Some(k), Some(v)) = (kv.next(), kv.next()) {
+            if k.trim().eq_ignore_ascii_case("q") {
+                q = v
+                    .trim()
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|q| (0.0..=1.0).contains(q))?;
+            }
+        }
+    }
+    Some((media, q))
+}
+
+/// `*/*` < `type/*` < `type/subtype`, `None` when the range does not match.
+fn specificity(range: &str, essence: &str) -> Option<u8> {
+    if range == "*/*" {
+        return Some(0);
+    }
+    let (ty, subtype) = split(range)?;
+    let (offer_ty, offer_subtype) = split(essence)?;
+    if !ty.eq_ignore_ascii_case(offer_ty) {
+        None
+    } else if subtype == "*" {
+        Some(1)
+    } else if subtype.eq_ignore_ascii_case(offer_subtype) {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn split(media: &str) -> Option<(&str, &str)> {
+    let mut parts = media.splitn(2, '/');
+    Some((parts.next()?, parts.next()?))
+}
+
+type Render<T> = Box<dyn FnOnce(&T) -> Response + Send>;
+
+/// Responds with the representation of a value the client accepts, or
+/// `406 Not Acceptable`.
+///
+/// JSON is always offered, HTML and plain text when a render closure is
+/// given, MessagePack and CBOR when asked for and their features are on.
+/// On a tie the formats are preferred in the order they were added.
+///
+/// ```ignore
+/// Negotiate::new(&cx, user)
+///     .html(|user| format!("<h1>{}</h1>", user.name))
+///     .text(|user| user.name.clone())
+/// ```
+pub struct Negotiate<T> {
+    accept: Option<HeaderValue>,
+    value: T,
+    formats: Vec<(&'static str, Render<T>)>,
+}
+
+impl<T: serde::Serialize + Send + 'static> Negotiate<T> {
+    pub fn new<S: Send + Sync + 'static>(cx: &Context<S>, value: T) -> Self {
+        Self {
+            accept: cx.headers().get(ACCEPT).cloned(),
+            value,
+            formats: vec![("application/json", Box::new(|v: &T| json(v)))],
+        }
+    }
+
+    /// Offers a custom media type.
+    pub fn format(
+        mut self,
+        mime: &'static str,
+        render: impl FnOnce(&T) -> Response + Send + 'static,
+    ) -> Self {
+        self.formats.push((mime, Box::new(render)));
+        self
+    }
+
+    /// Offers `text/html`.
+    pub fn html(self, render: impl FnOnce(&T) -> String + Send + 'static) -> Self {
+        self.format("text/html", |v: &T| crate::html(render(v)))
+    }
+
+    /// Offers `text/plain`.
+    pub fn text(self, render: impl FnOnce(&T) -> String + Send + 'static) -> Self {
+        self.format("text/plain", |v: &T| render(v).into_response())
+    }
+
+    /// Offers `application/msgpack`.
+    #[cfg(feature = "msgpack")]
+    pub fn msgpack(self) -> Self {
+        self.format("application/msgpack", |v: &T| crate::msgpack(v))
+    }
+
+    /// Offers `application/cbor`.
+    #[cfg(feature = "cbor")]
+    pub fn cbor(self) -> Self {
+        self.format("application/cbor", |v: &T| crate::cbor(v))
+    }
+}
+
+impl<T: Send> IntoResponse for Negotiate<T> {
+    fn into_response(self) -> Response {
+        let Self {
+            accept,
+            value,
+            mut formats,
+        } = self;
+
+        let offers = formats.iter().map(|(mime, _)| *mime).collect::<Vec<_>>();
+        let accept = accept.as_ref().and_then(|v| v.to_str().ok());
+
+        let mut res = match best(accept, &offers)
+            .and_then(|mime| formats.iter().position(|(m, _)| *m == mime))
+        {
+            Some(i) => (formats.swap_remove(i).1)(&value),
+            None => {
+                let mut res = Response::new(Body::from(format!(
+                    "not acceptable: available types are {}",
+                    offers.join(", ")
+                )));
+                *res.status_mut() = StatusCode::NOT_ACCEPTABLE;
+                res.headers_mut().insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_static("text/plain; charset=utf-8"),
+                );
+                res
+            }
+        };
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("accept"));
+        res
+    }
+}
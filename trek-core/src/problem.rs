@@ -0,0 +1,162 @@
+//! RFC 7807 problem details.
+
+use http::request::Parts;
+use hyper::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Body, StatusCode,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{error, fmt};
+
+use crate::{Error, ErrorResponse, Response};
+
+/// An `application/problem+json` document.
+///
+/// ```ignore
+/// Err(Problem::new(StatusCode::FORBIDDEN)
+///     .with_type("https://example.com/probs/out-of-credit")
+///     .title("You do not have enough credit.")
+///     .detail("Your current balance is 30, but that costs 50.")
+///     .extension("balance", 30))?
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    type_: String,
+    title: String,
+    #[serde(serialize_with = "serialize_status")]
+    status: StatusCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instance: Option<String>,
+    #[serde(flatten)]
+    extensions: Map<String, Value>,
+}
+
+fn serialize_status<S: serde::Serializer>(status: &StatusCode, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u16(status.as_u16())
+}
+
+impl Problem {
+    /// A problem of type `about:blank`, titled by the reason of the status.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_: "about:blank".to_owned(),
+            title: status.canonical_reason().unwrap_or("Unknown").to_owned(),
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// A problem with the status of an error's response. The `Display` of
+    /// the error is the detail of client errors only, so server errors do
+    /// not leak internals.
+    pub fn from_error(e: &dyn ErrorResponse) -> Self {
+        let problem = Self::new(e.error_response().status());
+        if problem.status.is_client_error() {
+            problem.detail(e.to_string())
+        } else {
+            problem
+        }
+    }
+
+    /// A URI identifying the problem type.
+    pub fn with_type(mut self, type_: impl Into<String>) -> Self {
+        self.type_ = type_.into();
+        self
+    }
+
+    /// A short summary of the problem type.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// An explanation of this occurrence of the problem.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// A URI identifying this occurrence of the problem.
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// An extension member, ignored when it fails to serialize or uses the
+    /// name of a standard member.
+    pub fn extension(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        let name = name.into();
+        let standard = ["type", "title", "status", "detail", "instance"];
+        match serde_json::to_value(value) {
+            Ok(value) if !standard.contains(&name.as_str()) => {
+                self.extensions.insert(name, value);
+            }
+            Ok(_) => log::warn!("problem extension `{}` is a standard member", name),
+            Err(e) => log::error!("problem extension `{}`: {}", name, e),
+        }
+        self
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", self.title, detail),
+            None => f.write_str(&self.title),
+        }
+    }
+}
+
+impl error::Error for Problem {}
+
+impl ErrorResponse for Problem {
+    fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> Response {
+        let body = serde_json::to_vec(self).unwrap_or_default();
+        let mut res = Response::new(Body::from(body));
+        *res.status_mut() = self.status;
+        res.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        res
+    }
+
+    fn render_response(&self) -> Response {
+        self.error_response()
+    }
+}
+
+/// An error handler rendering every error as a problem document, with the
+/// request path as the instance. Errors that already respond with a problem
+/// document are kept as they are.
+///
+/// ```ignore
+/// app.error_handler(problem_handler);
+/// ```
+pub fn problem_handler(err: Error, parts: &Parts) -> Response {
+    let res = err.as_error_response().error_response();
+    let is_problem = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .map_or(false, |v| v == "application/problem+json");
+    if is_problem {
+        return res;
+    }
+    Problem::from_error(err.as_error_response())
+        .instance(parts.uri.path())
+        .error_response()
+}
@@ -0,0 +1,172 @@
+//! Errors of extracting request data.
+
+use hyper::{header, Body, StatusCode};
+use std::{error, fmt, io, time::Duration};
+
+use crate::{ErrorResponse, Response};
+
+#[derive(Debug)]
+pub enum Rejection {
+    /// A route param is missing.
+    MissingParam(String),
+    /// The route params can not be deserialized.
+    InvalidParam(String),
+    /// The query string can not be deserialized.
+    InvalidQuery(String),
+    /// The JSON body is malformed, or does not match the expected type when
+    /// `syntax` is false.
+    InvalidJson {
+        message: String,
+        line: usize,
+        column: usize,
+        syntax: bool,
+    },
+    /// The urlencoded form body can not be deserialized.
+    InvalidForm(String),
+    /// The body can not be deserialized from a binary format such as
+    /// MessagePack or CBOR.
+    InvalidFormat {
+        format: &'static str,
+        message: String,
+    },
+    /// The body can not be read or is not UTF-8.
+    InvalidBody(String),
+    /// The body is larger than the limit.
+    PayloadTooLarge(usize),
+    /// A line of a newline delimited body is larger than the limit.
+    LineTooLong(usize),
+    /// No chunk of the body arrived in time.
+    BodyTimeout(Duration),
+    /// The `content-type` is not the expected one.
+    UnsupportedMediaType(&'static str),
+    /// The request is not a valid WebSocket upgrade.
+    InvalidUpgrade(&'static str),
+    /// The `sec-websocket-version` of a WebSocket upgrade is missing or not
+    /// 13, answered with `426` and the supported version.
+    UnsupportedWebSocketVersion,
+    /// No value of this type was set on the context.
+    MissingExtension(&'static str),
+    /// The body was already taken and not buffered, see
+    /// `Context::buffer_body`.
+    BodyTaken,
+}
+
+impl Rejection {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::MissingParam(_)
+            | Self::InvalidParam(_)
+            | Self::InvalidQuery(_)
+            | Self::InvalidForm(_)
+            | Self::InvalidFormat { .. }
+            | Self::InvalidBody(_)
+            | Self::InvalidUpgrade(_) => StatusCode::BAD_REQUEST,
+            Self::InvalidJson { syntax: true, .. } => StatusCode::BAD_REQUEST,
+            Self::InvalidJson { syntax: false, .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::PayloadTooLarge(_) | Self::LineTooLong(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::BodyTimeout(_) => StatusCode::REQUEST_TIMEOUT,
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::UnsupportedWebSocketVersion => StatusCode::UPGRADE_REQUIRED,
+            Self::MissingExtension(_) | Self::BodyTaken => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Splits `missing field `name`` errors of the params deserializer off
+    /// the other ones.
+    pub(crate) fn params(e: impl fmt::Display) -> Self {
+        const PREFIX: &str = "missing field `";
+        let message = e.to_string();
+        if message.starts_with(PREFIX) && message.ends_with('`') && message.len() > PREFIX.len() {
+            Self::MissingParam(message[PREFIX.len()..message.len() - 1].to_owned())
+        } else {
+            Self::InvalidParam(message)
+        }
+    }
+
+    pub(crate) fn json(e: serde_json::Error) -> Self {
+        Self::InvalidJson {
+            line: e.line(),
+            column: e.column(),
+            syntax: e.is_syntax() || e.is_eof(),
+            message: e.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingParam(name) => write!(f, "missing path param `{}`", name),
+            Self::InvalidParam(e) => write!(f, "invalid path params: {}", e),
+            Self::InvalidQuery(e) => write!(f, "invalid query string: {}", e),
+            Self::InvalidJson { message, .. } => write!(f, "invalid JSON body: {}", message),
+            Self::InvalidForm(e) => write!(f, "invalid form body: {}", e),
+            Self::InvalidFormat { format, message } => {
+                write!(f, "invalid {} body: {}", format, message)
+            }
+            Self::InvalidBody(e) => write!(f, "invalid request body: {}", e),
+            Self::PayloadTooLarge(limit) => {
+                write!(f, "payload too large: the limit is {} bytes", limit)
+            }
+            Self::LineTooLong(limit) => {
+                write!(f, "line too long: the limit is {} bytes per line", limit)
+            }
+            Self::BodyTimeout(timeout) => {
+                write!(f, "request body timed out after {:?}", timeout)
+            }
+            Self::UnsupportedMediaType(expected) => {
+                write!(f, "unsupported media type: expected `{}`", expected)
+            }
+            Self::InvalidUpgrade(reason) => write!(f, "invalid websocket upgrade: {}", reason),
+            Self::UnsupportedWebSocketVersion => {
+                f.write_str("unsupported websocket version: expected 13")
+            }
+            Self::MissingExtension(t) => write!(f, "missing extension: {}", t),
+            Self::BodyTaken => f.write_str("the request body was already taken"),
+        }
+    }
+}
+
+impl error::Error for Rejection {}
+
+/// Carries the rejection through the `io::Result` of the `Context` methods,
+/// client errors are `InvalidData`.
+impl From<Rejection> for io::Error {
+    fn from(r: Rejection) -> Self {
+        let kind = if let Rejection::BodyTimeout(_) = r {
+            io::ErrorKind::TimedOut
+        } else if r.status().is_client_error() {
+            io::ErrorKind::InvalidData
+        } else {
+            io::ErrorKind::Other
+        };
+        io::Error::new(kind, r)
+    }
+}
+
+/// Unwraps a `Rejection` carried by an `io::Error` of the `Context` methods,
+/// other errors are an invalid body.
+impl From<io::Error> for Rejection {
+    fn from(e: io::Error) -> Self {
+        let message = e.to_string();
+        e.into_inner()
+            .and_then(|e| e.downcast::<Rejection>().ok())
+            .map_or_else(|| Self::InvalidBody(message), |rejection| *rejection)
+    }
+}
+
+impl ErrorResponse for Rejection {
+    fn status(&self) -> StatusCode {
+        Rejection::status(self)
+    }
+
+    fn error_response(&self) -> Response {
+        let mut res = http::Response::builder()
+            .status(self.status())
+            .header(header::CONTENT_TYPE, "text/plain; charset=utf-8");
+        if let Self::UnsupportedWebSocketVersion = self {
+            res = res.header(header::SEC_WEBSOCKET_VERSION, "13");
+        }
+        res.body(Body::from(self.to_string())).unwrap()
+    }
+}
@@ -125,3 +125,43 @@ where
         .body(t.into())
         .unwrap()
 }
+
+#[cfg(feature = "msgpack")]
+pub fn msgpack<T>(t: &T) -> Response
+where
+    T: serde::Serialize,
+{
+    let res = http::Response::builder();
+
+    match rmp_serde::to_vec_named(t) {
+        Ok(v) => res
+            .header(CONTENT_TYPE, "application/msgpack")
+            .body(Body::from(v)),
+        Err(e) => {
+            log::error!("{}", e);
+            res.status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::empty())
+        }
+    }
+    .unwrap()
+}
+
+#[cfg(feature = "cbor")]
+pub fn cbor<T>(t: &T) -> Response
+where
+    T: serde::Serialize,
+{
+    let res = http::Response::builder();
+
+    match serde_cbor::to_vec(t) {
+        Ok(v) => res
+            .header(CONTENT_TYPE, "application/cbor")
+            .body(Body::from(v)),
+        Err(e) => {
+            log::error!("{}", e);
+            res.status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::empty())
+        }
+    }
+    .unwrap()
+}
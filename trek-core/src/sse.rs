@@ -0,0 +1,213 @@
+//! Server-Sent Events.
+//!
+//! ```ignore
+//! async fn events(cx: Context<State>) -> impl IntoResponse {
+//!     let since = cx.last_event_id().and_then(|id| id.parse().ok());
+//!     let events = cx.state().updates(since).map(|update| {
+//!         Event::default().id(update.id.to_string()).json(&update)
+//!     });
+//!     Sse::new(events)
+//! }
+//! ```
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{future::Future, stream::Stream};
+use futures_timer::Delay;
+use hyper::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use serde::Serialize;
+use std::{
+    convert::Infallible,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{Body, IntoResponse, Response};
+
+/// The default interval of keep-alive comments, 15 seconds.
+pub const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// One event of an event stream.
+#[derive(Debug, Clone, Default)]
+pub struct Event {
+    id: Option<String>,
+    event: Option<String>,
+    data: Option<String>,
+    retry: Option<Duration>,
+    comment: Option<String>,
+}
+
+impl Event {
+    /// The `data` field, split on newlines into several `data:` lines.
+    pub fn data(mut self, data: impl Into<String>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// The `data` field serialized as JSON.
+    pub fn json<T: Serialize>(self, data: &T) -> Self {
+        match serde_json::to_string(data) {
+            Ok(data) => self.data(data),
+            Err(e) => {
+                log::error!("{}", e);
+                self
+            }
+        }
+    }
+
+    /// The `event` field, the type of the event.
+    pub fn event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(single_line(event.into()));
+        self
+    }
+
+    /// The `id` field, sent back as `last-event-id` when the client
+    /// reconnects.
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(single_line(id.into()));
+        self
+    }
+
+    /// The `retry` field, how long the client waits before reconnecting.
+    pub fn retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// A comment line, ignored by clients.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    fn to_bytes(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        if let Some(comment) = &self.comment {
+            for line in comment.split('\n') {
+                field(&mut buf, "", line.trim_end_matches('\r'));
+            }
+        }
+        if let Some(event) = &self.event {
+            field(&mut buf, "event", event);
+        }
+        if let Some(data) = &self.data {
+            for line in data.split('\n') {
+                field(&mut buf, "data", line.trim_end_matches('\r'));
+            }
+        }
+        if let Some(id) = &self.id {
+            field(&mut buf, "id", id);
+        }
+        if let Some(retry) = &self.retry {
+            field(&mut buf, "retry", &retry.as_millis().to_string());
+        }
+        buf.put_u8(b'\n');
+        buf.freeze()
+    }
+}
+
+fn single_line(s: String) -> String {
+    s.replace(|c| c == '\n' || c == '\r', "")
+}
+
+fn field(buf: &mut BytesMut, name: &str, value: &str) {
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(b": ");
+    buf.extend_from_slice(value.as_bytes());
+    buf.put_u8(b'\n');
+}
+
+/// Responds with `text/event-stream`, sending a keep-alive comment whenever
+/// no event was sent for the keep-alive interval.
+///
+/// The stream of events is dropped as soon as the client disconnects, which
+/// is noticed at the latest on the next keep-alive, so resources owned by the
+/// stream are released then. `on_close` runs a callback at that point too.
+pub struct Sse {
+    stream: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    keep_alive: Option<Duration>,
+    on_close: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl Sse {
+    pub fn new(stream: impl Stream<Item = Event> + Send + 'static) -> Self {
+        Self {
+            stream: Box::pin(stream),
+            keep_alive: Some(DEFAULT_KEEP_ALIVE),
+            on_close: None,
+        }
+    }
+
+    /// Sets the keep-alive interval, `None` turns keep-alive comments off.
+    pub fn keep_alive(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+
+    /// Runs `f` when the stream ends or the client disconnects.
+    pub fn on_close(mut self, f: impl FnOnce() + Send + 'static) -> Self {
+        self.on_close = Some(Box::new(f));
+        self
+    }
+}
+
+impl fmt::Debug for Sse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sse")
+            .field("keep_alive", &self.keep_alive)
+            .finish()
+    }
+}
+
+impl IntoResponse for Sse {
+    fn into_response(self) -> Response {
+        let stream = SseStream {
+            delay: self.keep_alive.map(Delay::new),
+            inner: self,
+        };
+        let mut res = Response::new(Body::wrap_stream(stream));
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+        res.headers_mut()
+            .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        res
+    }
+}
+
+struct SseStream {
+    inner: Sse,
+    delay: Option<Delay>,
+}
+
+impl Stream for SseStream {
+    type Item = Result<Bytes, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        if let Poll::Ready(event) = this.inner.stream.as_mut().poll_next(cx) {
+            if let (Some(delay), Some(interval)) = (&mut this.delay, this.inner.keep_alive) {
+                delay.reset(interval);
+            }
+            return Poll::Ready(event.map(|event| Ok(event.to_bytes())));
+        }
+
+        if let (Some(delay), Some(interval)) = (&mut this.delay, this.inner.keep_alive) {
+            if Pin::new(&mut *delay).poll(cx).is_ready() {
+                delay.reset(interval);
+                return Poll::Ready(Some(Ok(Bytes::from_static(b":\n\n"))));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for SseStream {
+    fn drop(&mut self) {
+        if let Some(f) = self.inner.on_close.take() {
+            f();
+        }
+    }
+}
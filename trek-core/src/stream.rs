@@ -0,0 +1,157 @@
+//! Streaming responses.
+//!
+//! The body is sent with chunked transfer encoding as the stream yields.
+//! An error in the middle of a stream is logged and aborts the response, so
+//! the client sees an incomplete body instead of a truncated but seemingly
+//! complete one.
+
+use bytes::Bytes;
+use futures::{
+    io::AsyncRead,
+    ready,
+    stream::{Stream, StreamExt, TryStreamExt},
+};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use serde::Serialize;
+use std::{
+    error::Error as StdError,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{Body, IntoResponse, Response};
+
+/// The default chunk size of `from_reader`, 8 KiB.
+pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+/// A response whose body is a stream of chunks.
+///
+/// ```ignore
+/// from_stream(rows.map(|row| Ok::<_, io::Error>(row.to_csv()))).content_type("text/csv")
+/// ```
+pub struct StreamBody<S> {
+    stream: S,
+    content_type: HeaderValue,
+}
+
+/// Responds with the chunks of a stream, `application/octet-stream` unless
+/// another content type is set.
+pub fn from_stream<S, O, E>(stream: S) -> StreamBody<S>
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn StdError + Send + Sync>> + 'static,
+{
+    StreamBody {
+        stream,
+        content_type: HeaderValue::from_static("application/octet-stream"),
+    }
+}
+
+/// Responds with the bytes of a reader, `DEFAULT_CHUNK_SIZE` at a time.
+///
+/// Takes a `futures::io::AsyncRead`, tokio readers can be adapted with
+/// `tokio-util`'s `compat` module.
+pub fn from_reader<R>(reader: R) -> StreamBody<ReaderStream<R>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    from_stream(ReaderStream::new(reader))
+}
+
+impl<S> StreamBody<S> {
+    pub fn content_type(mut self, content_type: &'static str) -> Self {
+        self.content_type = HeaderValue::from_static(content_type);
+        self
+    }
+}
+
+impl<R> StreamBody<ReaderStream<R>> {
+    /// Sets the size of the chunks read at a time.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.stream.chunk_size = chunk_size.max(1);
+        self
+    }
+}
+
+impl<S, O, E> IntoResponse for StreamBody<S>
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn StdError + Send + Sync>> + 'static,
+{
+    fn into_response(self) -> Response {
+        let stream =
+            self.stream
+                .map_err(Into::into)
+                .inspect_err(|e: &Box<dyn StdError + Send + Sync>| {
+                    log::error!("response stream aborted: {}", e)
+                });
+        let mut res = Response::new(Body::wrap_stream(stream));
+        res.headers_mut().insert(CONTENT_TYPE, self.content_type);
+        res
+    }
+}
+
+/// The chunks of an `AsyncRead`, see `from_reader`.
+pub struct ReaderStream<R> {
+    reader: Option<R>,
+    chunk_size: usize,
+    buf: Vec<u8>,
+}
+
+impl<R> ReaderStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(reader),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for ReaderStream<R> {
+    type Item = io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let reader = match this.reader.as_mut() {
+            Some(reader) => reader,
+            None => return Poll::Ready(None),
+        };
+        this.buf.resize(this.chunk_size, 0);
+        match ready!(Pin::new(reader).poll_read(cx, &mut this.buf)) {
+            Ok(0) => {
+                this.reader = None;
+                Poll::Ready(None)
+            }
+            Ok(n) => Poll::Ready(Some(Ok(Bytes::copy_from_slice(&this.buf[..n])))),
+            Err(e) => {
+                this.reader = None;
+                Poll::Ready(Some(Err(e)))
+            }
+        }
+    }
+}
+
+/// Responds with `application/x-ndjson`, one JSON line per item.
+///
+/// An item that fails to serialize aborts the response.
+pub struct NdJson<S>(pub S);
+
+impl<S, T> IntoResponse for NdJson<S>
+where
+    S: Stream<Item = T> + Send + 'static,
+    T: Serialize,
+{
+    fn into_response(self) -> Response {
+        from_stream(self.0.map(|item| {
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            Ok::<_, serde_json::Error>(line)
+        }))
+        .content_type("application/x-ndjson")
+        .into_response()
+    }
+}
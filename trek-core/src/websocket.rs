@@ -0,0 +1,220 @@
+//! WebSocket upgrades.
+//!
+//! ```ignore
+//! async fn chat(mut cx: Context<State>) -> Result {
+//!     let (res, ws) = cx.websocket()?.protocols(&["chat.v2", "chat.v1"]).upgrade();
+//!     tokio::spawn(async move {
+//!         let mut ws = match ws.await {
+//!             Ok(ws) => ws,
+//!             Err(e) => return error!("websocket upgrade failed: {}", e),
+//!         };
+//!         while let Some(Ok(msg)) = ws.next().await {
+//!             if msg.is_text() && ws.send(msg).await.is_err() {
+//!                 break;
+//!             }
+//!         }
+//!     });
+//!     Ok(res)
+//! }
+//! ```
+//!
+//! The `WebSocket` is a `Stream` of `Message`s and a `Sink` for them. Pings
+//! are answered with pongs automatically, a close frame from the client is
+//! echoed and ends the stream.
+//!
+//! Handlers are tested in-process with `websocket::request`, a valid upgrade
+//! request whose `101` response can be checked without a connection:
+//!
+//! ```ignore
+//! let req = websocket::request("/chat", &["chat.v1"]);
+//! let res = chat(Context::new(Arc::new(state), req, vec![], vec![])).await?;
+//! assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+//! assert_eq!(res.headers()["sec-websocket-accept"], websocket::SAMPLE_ACCEPT);
+//! ```
+
+use futures::future::{BoxFuture, FutureExt};
+use hyper::{
+    header::{
+        HeaderMap, HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY,
+        SEC_WEBSOCKET_PROTOCOL, SEC_WEBSOCKET_VERSION, UPGRADE,
+    },
+    upgrade::Upgraded,
+    Body, Method, StatusCode,
+};
+use sha1::{Digest, Sha1};
+use std::{fmt, io};
+use tokio_tungstenite::{
+    tungstenite::protocol::{Role, WebSocketConfig},
+    WebSocketStream,
+};
+
+pub use tokio_tungstenite::tungstenite::{
+    protocol::{frame::coding::CloseCode, CloseFrame},
+    Error, Message,
+};
+
+use crate::{Rejection, Request, Response};
+
+/// The `sec-websocket-key` of the requests built by `request`, the sample
+/// nonce of RFC 6455.
+pub const SAMPLE_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+
+/// The `sec-websocket-accept` answering `SAMPLE_KEY`.
+pub const SAMPLE_ACCEPT: &str = "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=";
+
+/// A valid upgrade request for `uri` with `SAMPLE_KEY`, requesting the
+/// `protocols` if any, for testing handlers in-process. Its body never
+/// upgrades, the `WebSocket` future of `upgrade` fails.
+pub fn request(uri: &str, protocols: &[&str]) -> Request {
+    let mut builder = http::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header(CONNECTION, "upgrade")
+        .header(UPGRADE, "websocket")
+        .header(SEC_WEBSOCKET_VERSION, "13")
+        .header(SEC_WEBSOCKET_KEY, SAMPLE_KEY);
+    if !protocols.is_empty() {
+        builder = builder.header(SEC_WEBSOCKET_PROTOCOL, protocols.join(", "));
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+/// An upgraded connection, a `Stream` and `Sink` of `Message`s.
+pub type WebSocket = WebSocketStream<Upgraded>;
+
+/// A validated upgrade request, see `Context::websocket`.
+pub struct WebSocketUpgrade {
+    key: HeaderValue,
+    requested: Vec<String>,
+    protocol: Option<HeaderValue>,
+    config: WebSocketConfig,
+    pub(crate) body: Body,
+}
+
+impl WebSocketUpgrade {
+    /// Validates the upgrade headers of a request, the body is set by the
+    /// context afterwards.
+    pub(crate) fn new(method: &Method, headers: &HeaderMap) -> Result<Self, Rejection> {
+        if method != Method::GET {
+            return Err(Rejection::InvalidUpgrade("the method must be GET"));
+        }
+        if !has_token(headers, CONNECTION.as_str(), "upgrade") {
+            return Err(Rejection::InvalidUpgrade("missing `connection: upgrade`"));
+        }
+        if !has_token(headers, UPGRADE.as_str(), "websocket") {
+            return Err(Rejection::InvalidUpgrade("missing `upgrade: websocket`"));
+        }
+        if headers.get(SEC_WEBSOCKET_VERSION) != Some(&HeaderValue::from_static("13")) {
+            return Err(Rejection::UnsupportedWebSocketVersion);
+        }
+        let key = headers
+            .get(SEC_WEBSOCKET_KEY)
+            .cloned()
+            .ok_or(Rejection::InvalidUpgrade("missing `sec-websocket-key`"))?;
+
+        let requested = headers
+            .get_all(SEC_WEBSOCKET_PROTOCOL)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(|p| p.trim().to_owned())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        Ok(Self {
+            key,
+            requested,
+            protocol: None,
+            config: WebSocketConfig::default(),
+            body: Body::empty(),
+        })
+    }
+
+    /// The subprotocols requested by the client, in its order of preference.
+    pub fn requested_protocols(&self) -> &[String] {
+        &self.requested
+    }
+
+    /// Selects the first subprotocol requested by the client that is
+    /// supported. No subprotocol is selected when none is supported.
+    pub fn protocols(mut self, supported: &[&'static str]) -> Self {
+        self.protocol = self
+            .requested
+            .iter()
+            .find_map(|p| supported.iter().find(|s| **s == p.as_str()))
+            .map(|p| HeaderValue::from_static(p));
+        self
+    }
+
+    /// The selected subprotocol.
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_ref().and_then(|p| p.to_str().ok())
+    }
+
+    /// The maximum size of an incoming message, 64 MiB by default.
+    pub fn max_message_size(mut self, size: usize) -> Self {
+        self.config.max_message_size = Some(size);
+        self
+    }
+
+    /// The maximum size of an incoming frame, 16 MiB by default.
+    pub fn max_frame_size(mut self, size: usize) -> Self {
+        self.config.max_frame_size = Some(size);
+        self
+    }
+
+    /// Returns the `101 Switching Protocols` response and a future resolving
+    /// to the `WebSocket` once the response was sent. The response must be
+    /// returned from the handler for the future to resolve.
+    pub fn upgrade(self) -> (Response, BoxFuture<'static, io::Result<WebSocket>>) {
+        let mut res = Response::new(Body::empty());
+        *res.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+        let headers = res.headers_mut();
+        headers.insert(CONNECTION, HeaderValue::from_static("upgrade"));
+        headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+        headers.insert(SEC_WEBSOCKET_ACCEPT, accept_key(self.key.as_bytes()));
+        if let Some(protocol) = self.protocol {
+            headers.insert(SEC_WEBSOCKET_PROTOCOL, protocol);
+        }
+
+        let config = self.config;
+        let ws = self
+            .body
+            .on_upgrade()
+            .map(move |upgraded| upgraded.map_err(|e| io::Error::new(io::ErrorKind::Other, e)))
+            .then(move |upgraded| async move {
+                Ok(WebSocketStream::from_raw_socket(upgraded?, Role::Server, Some(config)).await)
+            })
+            .boxed();
+
+        (res, ws)
+    }
+}
+
+impl fmt::Debug for WebSocketUpgrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketUpgrade")
+            .field("requested", &self.requested)
+            .field("protocol", &self.protocol)
+            .finish()
+    }
+}
+
+/// Whether a comma separated header has `token`, ignoring ASCII case.
+fn has_token(headers: &HeaderMap, name: &str, token: &str) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|t| t.trim().eq_ignore_ascii_case(token))
+}
+
+/// `base64(sha1(key + GUID))`, the `sec-websocket-accept` of a key.
+fn accept_key(key: &[u8]) -> HeaderValue {
+    const GUID: &[u8] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+    let mut sha1 = Sha1::new();
+    sha1.update(key);
+    sha1.update(GUID);
+    HeaderValue::from_str(&base64::encode(sha1.finalize())).unwrap()
+}
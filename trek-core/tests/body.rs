@@ -0,0 +1,77 @@
+use futures::{executor::block_on, future::BoxFuture};
+use hyper::{body::to_bytes, Body, StatusCode};
+use std::sync::Arc;
+use trek_core::{Context, ErrorResponse, Middleware, Rejection, Request, Response};
+
+fn request(body: &'static str) -> Request {
+    http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("content-type", "text/plain")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn context(body: &'static str, middleware: Vec<Arc<dyn Middleware<Context<()>>>>) -> Context<()> {
+    Context::new(Arc::new(()), request(body), vec![], middleware)
+}
+
+fn buffer(mut cx: Context<()>) -> BoxFuture<'static, Response> {
+    Box::pin(async move {
+        let body = cx.buffer_body().await.unwrap();
+        assert_eq!(&body[..], b"hello");
+        assert_eq!(cx.string().await.unwrap(), "hello");
+        cx.next().await
+    })
+}
+
+fn echo(mut cx: Context<()>) -> BoxFuture<'static, Response> {
+    Box::pin(async move {
+        match cx.string().await {
+            Ok(body) => Response::new(Body::from(body)),
+            Err(e) => Rejection::from(e).error_response(),
+        }
+    })
+}
+
+#[test]
+fn second_read_fails() {
+    block_on(async {
+        let mut cx = context("hello", vec![]);
+        assert_eq!(cx.string().await.unwrap(), "hello");
+
+        let e = Rejection::from(cx.string().await.unwrap_err());
+        match e {
+            Rejection::BodyTaken => {}
+            _ => panic!("expected BodyTaken, got {:?}", e),
+        }
+        assert_eq!(e.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(cx.take_body().is_err());
+    })
+}
+
+#[test]
+fn buffered_body_replays_downstream() {
+    block_on(async {
+        let cx = context("hello", vec![Arc::new(buffer), Arc::new(echo)]);
+        let res = cx.next().await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(&to_bytes(res.into_body()).await.unwrap()[..], b"hello");
+    })
+}
+
+#[test]
+fn unbuffered_body_is_gone_downstream() {
+    fn read(mut cx: Context<()>) -> BoxFuture<'static, Response> {
+        Box::pin(async move {
+            cx.bytes().await.unwrap();
+            cx.next().await
+        })
+    }
+
+    block_on(async {
+        let cx = context("hello", vec![Arc::new(read), Arc::new(echo)]);
+        let res = cx.next().await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    })
+}
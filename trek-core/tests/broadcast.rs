@@ -0,0 +1,97 @@
+use futures::{executor::block_on, StreamExt};
+use trek_core::broadcast::{Broadcast, LagPolicy, TopicStats};
+
+#[test]
+fn dropped_subscriptions_leave_their_topic() {
+    let events = Broadcast::<u32>::new();
+    let a = events.subscribe("a");
+    let b1 = events.subscribe("b");
+    let b2 = events.subscribe("b");
+    assert_eq!(events.subscribers("b"), 2);
+
+    drop(a);
+    drop(b1);
+    assert_eq!(events.topics(), vec!["b".to_owned()]);
+    assert_eq!(events.subscribers("a"), 0);
+    assert_eq!(events.subscribers("b"), 1);
+
+    drop(b2);
+    assert!(events.topics().is_empty());
+    assert_eq!(events.publish("b", 1), 0);
+}
+
+#[test]
+fn counters_outlive_the_topic() {
+    let events = Broadcast::new();
+    let mut sub = events.subscribe("t");
+    assert_eq!(events.publish("t", 1), 1);
+    assert_eq!(block_on(sub.next()), Some(1));
+    drop(sub);
+
+    let stats = events.stats("t").unwrap();
+    assert_eq!(stats.subscribers, 0);
+    assert_eq!(stats.published, 1);
+
+    let _sub = events.subscribe("t");
+    events.publish("t", 2);
+    assert_eq!(events.stats("t").unwrap().published, 2);
+
+    events.reset_stats("t");
+    assert_eq!(
+        events.stats("t"),
+        Some(TopicStats {
+            subscribers: 1,
+            ..TopicStats::default()
+        })
+    );
+    assert_eq!(events.stats("other"), None);
+}
+
+#[test]
+fn drop_policy_skips_messages_for_the_lagging_subscriber() {
+    let events = Broadcast::with_capacity(1).lag_policy(LagPolicy::Drop);
+    let mut slow = events.subscribe("t");
+    let mut fast = events.subscribe("t");
+
+    for i in 0..3 {
+        events.publish("t", i);
+        assert_eq!(block_on(fast.next()), Some(i));
+    }
+    assert_eq!(slow.lagged(), 2);
+    assert_eq!(fast.lagged(), 0);
+
+    let stats = events.stats("t").unwrap();
+    assert_eq!(stats.dropped, 2);
+    assert_eq!(stats.disconnected, 0);
+    assert_eq!(stats.subscribers, 2);
+
+    assert_eq!(block_on(slow.next()), Some(0));
+    assert_eq!(events.publish("t", 3), 2);
+    assert_eq!(block_on(slow.next()), Some(3));
+}
+
+#[test]
+fn disconnect_policy_ends_the_lagging_subscriber() {
+    let events = Broadcast::with_capacity(1).lag_policy(LagPolicy::Disconnect);
+    let mut slow = events.subscribe("t");
+    let mut fast = events.subscribe("t");
+
+    for i in 0..2 {
+        events.publish("t", i);
+        assert_eq!(block_on(fast.next()), Some(i));
+    }
+
+    let stats = events.stats("t").unwrap();
+    assert_eq!(stats.disconnected, 1);
+    assert_eq!(stats.dropped, 0);
+    assert_eq!(stats.subscribers, 1);
+
+    assert_eq!(block_on(slow.next()), Some(0));
+    assert_eq!(block_on(slow.next()), None);
+    assert_eq!(slow.lagged(), 0);
+
+    drop(fast);
+    assert!(events.topics().is_empty());
+    drop(slow);
+    assert_eq!(events.stats("t").unwrap().disconnected, 1);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "cbor")]
+
+use futures::executor::block_on;
+use hyper::{body::to_bytes, header::CONTENT_TYPE, Body};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use trek_core::{cbor, extract::Cbor, Context, IntoResponse, Rejection, StatusCode};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct User {
+    name: String,
+    age: u8,
+    tags: Vec<String>,
+}
+
+fn user() -> User {
+    User {
+        name: "ann".to_owned(),
+        age: 30,
+        tags: vec!["admin".to_owned()],
+    }
+}
+
+fn context(content_type: &str, body: Vec<u8>) -> Context<()> {
+    let req = http::Request::builder()
+        .method("POST")
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap();
+    Context::new(Arc::new(()), req, vec![], vec![])
+}
+
+fn encoded() -> Vec<u8> {
+    let res = cbor(&user());
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/cbor");
+    block_on(to_bytes(res.into_body())).unwrap().to_vec()
+}
+
+#[test]
+fn round_trip() {
+    let mut cx = context("application/cbor", encoded());
+    assert_eq!(block_on(cx.cbor::<User>()).unwrap(), user());
+
+    let mut cx = context("application/senml+cbor", encoded());
+    assert_eq!(block_on(cx.cbor::<User>()).unwrap(), user());
+}
+
+#[test]
+fn extractor_responds_in_kind() {
+    let res = Cbor(user()).into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/cbor");
+    let body = block_on(to_bytes(res.into_body())).unwrap().to_vec();
+    assert_eq!(body, encoded());
+}
+
+#[test]
+fn wrong_content_type() {
+    let mut cx = context("application/json", encoded());
+    let e = Rejection::from(block_on(cx.cbor::<User>()).unwrap_err());
+    match &e {
+        Rejection::UnsupportedMediaType(expected) => assert_eq!(*expected, "application/cbor"),
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[test]
+fn invalid_body() {
+    let mut cx = context("application/cbor", b"\xc1 not cbor".to_vec());
+    let e = Rejection::from(block_on(cx.cbor::<User>()).unwrap_err());
+    match &e {
+        Rejection::InvalidFormat { .. } => {}
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+}
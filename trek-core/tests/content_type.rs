@@ -0,0 +1,87 @@
+use futures::executor::block_on;
+use hyper::{Body, StatusCode};
+use serde::Deserialize;
+use std::{collections::HashMap, sync::Arc};
+use trek_core::{Context, Rejection};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Point {
+    x: i32,
+}
+
+fn context(content_type: Option<&str>, body: &'static str) -> Context<()> {
+    let mut builder = http::Request::builder().method("POST").uri("/");
+    if let Some(content_type) = content_type {
+        builder = builder.header("content-type", content_type);
+    }
+    let req = builder.body(Body::from(body)).unwrap();
+    Context::new(Arc::new(()), req, vec![], vec![])
+}
+
+fn json(content_type: Option<&str>) -> Result<Point, Rejection> {
+    let mut cx = context(content_type, r#"{"x":1}"#);
+    block_on(cx.json()).map_err(Rejection::from)
+}
+
+fn form(content_type: Option<&str>) -> Result<HashMap<String, String>, Rejection> {
+    let mut cx = context(content_type, "x=1");
+    block_on(cx.form()).map_err(Rejection::from)
+}
+
+fn assert_unsupported<T: std::fmt::Debug>(result: Result<T, Rejection>, expected: &str) {
+    match result {
+        Err(Rejection::UnsupportedMediaType(media_type)) => assert_eq!(media_type, expected),
+        other => panic!("expected 415, got {:?}", other),
+    }
+}
+
+#[test]
+fn json_content_types() {
+    assert_eq!(json(Some("application/json")).unwrap(), Point { x: 1 });
+    assert!(json(Some("Application/JSON")).is_ok());
+    assert!(json(Some("application/json; charset=utf-8")).is_ok());
+    assert!(json(Some("application/json;charset=\"UTF-8\"")).is_ok());
+    assert!(json(Some("application/problem+json")).is_ok());
+    assert!(json(Some("application/vnd.api+json; charset=utf8")).is_ok());
+
+    assert_unsupported(json(None), "application/json");
+    assert_unsupported(json(Some("text/plain")), "application/json");
+    assert_unsupported(json(Some("text/json+xml")), "application/json");
+    assert_unsupported(json(Some("application/jsonp")), "application/json");
+    assert_unsupported(
+        json(Some("application/json; charset=latin1")),
+        "application/json",
+    );
+}
+
+#[test]
+fn form_content_types() {
+    assert_eq!(
+        form(Some("application/x-www-form-urlencoded")).unwrap()["x"],
+        "1"
+    );
+    assert!(form(Some("application/x-www-form-urlencoded; charset=UTF-8")).is_ok());
+
+    let expected = "application/x-www-form-urlencoded";
+    assert_unsupported(form(None), expected);
+    assert_unsupported(form(Some("application/json")), expected);
+    assert_unsupported(form(Some("multipart/form-data; boundary=x")), expected);
+}
+
+#[test]
+fn unsupported_media_type_status() {
+    let rejection = json(Some("text/plain")).unwrap_err();
+    assert_eq!(rejection.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[test]
+fn lenient_content_type() {
+    let mut cx = context(Some("text/plain"), r#"{"x":1}"#);
+    cx.set_strict_content_type(false);
+    assert_eq!(block_on(cx.json::<Point>()).unwrap(), Point { x: 1 });
+
+    let mut cx = context(None, "x=1");
+    cx.set_strict_content_type(false);
+    let form: HashMap<String, String> = block_on(cx.form()).unwrap();
+    assert_eq!(form["x"], "1");
+}
@@ -0,0 +1,47 @@
+use futures::executor::block_on;
+use hyper::{body::to_bytes, header::CONTENT_TYPE, StatusCode};
+use std::{error, fmt};
+use trek_core::{debug::debug_page, Error, ErrorResponse};
+
+#[derive(Debug)]
+struct Failure;
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<failure>")
+    }
+}
+
+impl error::Error for Failure {}
+
+impl ErrorResponse for Failure {}
+
+fn parts() -> http::request::Parts {
+    let req = http::Request::builder().uri("/users/1").body(()).unwrap();
+    req.into_parts().0
+}
+
+#[test]
+fn debug_page_in_debug_builds_only() {
+    let err: Error = Failure.into();
+    let res = debug_page(&err, &parts());
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let html = res
+        .headers()
+        .get(CONTENT_TYPE)
+        .map_or(false, |v| v.to_str().unwrap().starts_with("text/html"));
+    assert_eq!(html, cfg!(debug_assertions));
+    let body = block_on(to_bytes(res.into_body())).unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert_eq!(body.contains("&lt;failure&gt;"), cfg!(debug_assertions));
+}
+
+#[cfg(feature = "backtrace")]
+#[test]
+fn backtraces_of_server_errors_only() {
+    let server: Error = Failure.into();
+    let client: Error = trek_core::Rejection::InvalidQuery("x".to_owned()).into();
+    assert_eq!(server.backtrace().is_some(), cfg!(debug_assertions));
+    assert!(client.backtrace().is_none());
+}
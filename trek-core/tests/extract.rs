@@ -0,0 +1,146 @@
+use futures::executor::block_on;
+use hyper::{body::to_bytes, header::HeaderMap, Body, StatusCode};
+use serde::Deserialize;
+use std::sync::Arc;
+use trek_core::{
+    extract::{with, Extension, Form, Json, Path, Query, State},
+    json, Context, Handler, Request, Response,
+};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    name: String,
+    age: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    page: u32,
+}
+
+fn request(uri: &str, content_type: &str, body: &'static str) -> Request {
+    http::Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+fn context(req: Request) -> Context<&'static str> {
+    let params = vec![("id".to_owned(), "7".to_owned())];
+    Context::new(Arc::new("state"), req, params, vec![])
+}
+
+fn call(h: impl Handler<Context<&'static str>>, req: Request) -> (StatusCode, String) {
+    block_on(async {
+        let res: Response = h.call(context(req)).await;
+        let status = res.status();
+        let body = to_bytes(res.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    })
+}
+
+fn json_user() -> Request {
+    request("/?page=2", "application/json", r#"{"name":"ann","age":30}"#)
+}
+
+#[test]
+fn extracts_the_arguments() {
+    let h = with(
+        |Path(id): Path<u64>, Query(q): Query<Page>, Json(user): Json<User>| async move {
+            format!("{} {} {} {}", id, q.page, user.name, user.age)
+        },
+    );
+    assert_eq!(
+        call(h, json_user()),
+        (StatusCode::OK, "7 2 ann 30".to_owned())
+    );
+}
+
+#[test]
+fn extracts_form_state_and_headers() {
+    let h = with(
+        |Form(user): Form<User>, State(state): State<&'static str>, headers: HeaderMap| async move {
+            format!(
+                "{} {} {}",
+                user.name,
+                state,
+                headers.contains_key("content-type")
+            )
+        },
+    );
+    let req = request("/", "application/x-www-form-urlencoded", "name=bo&age=4");
+    assert_eq!(call(h, req), (StatusCode::OK, "bo state true".to_owned()));
+}
+
+#[test]
+fn json_responds_with_json() {
+    let h = with(|Json(user): Json<User>| async move { json(&user.age) });
+    assert_eq!(call(h, json_user()), (StatusCode::OK, "30".to_owned()));
+}
+
+#[test]
+fn path_rejections() {
+    let missing = with(|Path(p): Path<(u64, u64)>| async move { format!("{:?}", p) });
+    assert_eq!(call(missing, json_user()).0, StatusCode::BAD_REQUEST);
+
+    #[derive(Deserialize)]
+    struct Named {
+        #[allow(dead_code)]
+        slug: String,
+    }
+    let h = with(|_: Path<Named>| async { "" });
+    let (status, body) = call(h, json_user());
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body, "missing path param `slug`");
+
+    let h = with(|_: Path<bool>| async { "" });
+    assert_eq!(call(h, json_user()).0, StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn query_rejection() {
+    let h = with(|_: Query<Page>| async { "" });
+    let req = request("/?page=two", "application/json", "");
+    let (status, body) = call(h, req);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.starts_with("invalid query string"), "{}", body);
+}
+
+#[test]
+fn json_rejections() {
+    let h = with(|_: Json<User>| async { "" });
+    let syntax = request("/", "application/json", r#"{"name":"#);
+    assert_eq!(call(h.clone(), syntax).0, StatusCode::BAD_REQUEST);
+    let data = request("/", "application/json", r#"{"name":"ann","age":300}"#);
+    assert_eq!(call(h.clone(), data).0, StatusCode::UNPROCESSABLE_ENTITY);
+    let text = request("/", "text/plain", r#"{"name":"ann","age":30}"#);
+    assert_eq!(call(h, text).0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[test]
+fn form_rejections() {
+    let h = with(|_: Form<User>| async { "" });
+    let req = request("/", "application/x-www-form-urlencoded", "name=bo&age=old");
+    assert_eq!(call(h.clone(), req).0, StatusCode::BAD_REQUEST);
+    let req = request("/", "application/json", "name=bo&age=4");
+    assert_eq!(call(h, req).0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[test]
+fn extension_rejection() {
+    let h = with(|Extension(n): Extension<u32>| async move { n.to_string() });
+    let (status, body) = call(h, json_user());
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(body, "missing extension: u32");
+}
+
+#[test]
+fn stops_at_the_first_rejection() {
+    let h = with(|_: Query<Page>, _: Json<User>| async { "" });
+    let req = request("/?page=x", "text/plain", "");
+    let (status, body) = call(h, req);
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.starts_with("invalid query string"), "{}", body);
+}
@@ -0,0 +1,82 @@
+use futures::{executor::block_on, stream};
+use hyper::{Body, StatusCode};
+use std::{io, sync::Arc};
+use trek_core::{Context, Rejection};
+
+fn context(content_type: &str, content_length: Option<usize>, body: Body) -> Context<()> {
+    let mut req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("content-type", content_type);
+    if let Some(len) = content_length {
+        req = req.header("content-length", len);
+    }
+    Context::new(Arc::new(()), req.body(body).unwrap(), vec![], vec![])
+}
+
+fn chunked(chunks: &[&'static str]) -> Body {
+    let chunks: Vec<io::Result<&'static str>> = chunks.iter().map(|c| Ok(*c)).collect();
+    Body::wrap_stream(stream::iter(chunks))
+}
+
+fn assert_too_large(e: io::Error, limit: usize) {
+    match Rejection::from(e) {
+        Rejection::PayloadTooLarge(l) => assert_eq!(l, limit),
+        r => panic!("expected PayloadTooLarge, got {:?}", r),
+    }
+}
+
+#[test]
+fn content_length_is_rejected_before_reading() {
+    // The sender is never used, reading the body would wait forever.
+    let (_sender, body) = Body::channel();
+    let mut cx = context("text/plain", Some(100), body);
+    assert_too_large(block_on(cx.bytes_with_limit(10)).unwrap_err(), 10);
+}
+
+#[test]
+fn streamed_body_crossing_the_limit() {
+    let mut cx = context("text/plain", None, chunked(&["abc", "def", "ghi"]));
+    assert_too_large(block_on(cx.bytes_with_limit(5)).unwrap_err(), 5);
+
+    let mut cx = context("text/plain", None, chunked(&["abc", "de"]));
+    assert_eq!(&block_on(cx.bytes_with_limit(5)).unwrap()[..], b"abcde");
+}
+
+#[test]
+fn default_and_per_context_limit() {
+    let mut cx = context("text/plain", None, chunked(&["abc", "def"]));
+    cx.set_body_limit(4);
+    let e = block_on(cx.string()).unwrap_err();
+    assert_eq!(Rejection::from(e).status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[test]
+fn per_call_limits() {
+    let json = r#"{"name":"trek"}"#;
+
+    let mut cx = context("application/json", Some(json.len()), Body::from(json));
+    let e = block_on(cx.json_with_limit::<serde_json::Value>(4)).unwrap_err();
+    assert_too_large(e, 4);
+
+    let mut cx = context("application/json", Some(json.len()), Body::from(json));
+    cx.set_body_limit(4);
+    let v: serde_json::Value = block_on(cx.json_with_limit(1024)).unwrap();
+    assert_eq!(v["name"], "trek");
+
+    let form = "name=trek";
+    let mut cx = context("application/x-www-form-urlencoded", None, chunked(&[form]));
+    let e = block_on(cx.form_with_limit::<Vec<(String, String)>>(4)).unwrap_err();
+    assert_too_large(e, 4);
+
+    let mut cx = context("text/plain", None, chunked(&["abc", "def"]));
+    assert_too_large(block_on(cx.string_with_limit(4)).unwrap_err(), 4);
+}
+
+#[test]
+fn buffered_body_is_checked_again() {
+    let mut cx = context("text/plain", None, chunked(&["abc", "def"]));
+    block_on(cx.buffer_body()).unwrap();
+    assert_too_large(block_on(cx.bytes_with_limit(4)).unwrap_err(), 4);
+    assert_eq!(block_on(cx.string()).unwrap(), "abcdef");
+}
@@ -0,0 +1,55 @@
+use futures::{executor::block_on, future::FutureExt};
+use hyper::{body::to_bytes, Body, StatusCode};
+use std::{sync::Arc, time::Duration};
+use trek_core::{
+    long_poll::{long_poll, Changes},
+    Context,
+};
+
+fn poll(changes: &Changes, since: Option<u64>) -> (StatusCode, String) {
+    let uri = since.map_or_else(|| "/".to_owned(), |since| format!("/?since={}", since));
+    let req = http::Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let cx = Context::new(Arc::new(()), req, vec![], vec![]);
+    block_on(async {
+        let res = long_poll(&cx, changes, Duration::from_millis(10), |v| v * 10)
+            .await
+            .unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    })
+}
+
+#[test]
+fn changed_waits_for_a_newer_version() {
+    let changes = Changes::new();
+    let mut changed = changes.changed(0).boxed();
+    assert_eq!((&mut changed).now_or_never(), None);
+    changes.bump();
+    assert_eq!(changed.now_or_never(), Some(1));
+}
+
+#[test]
+fn changed_resolves_when_since_is_ahead() {
+    let changes = Changes::new();
+    changes.bump();
+    assert_eq!(changes.changed(7).now_or_never(), Some(1));
+}
+
+#[test]
+fn long_poll_responses() {
+    let changes = Changes::new();
+    changes.bump();
+    changes.bump();
+
+    let (status, body) = poll(&changes, None);
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"version":2,"data":20}"#);
+
+    assert_eq!(poll(&changes, Some(2)).0, StatusCode::NO_CONTENT);
+    assert_eq!(poll(&changes, Some(1)).1, r#"{"version":2,"data":20}"#);
+    assert_eq!(poll(&changes, Some(40)).1, r#"{"version":2,"data":20}"#);
+}
@@ -0,0 +1,92 @@
+use futures::{executor::block_on, stream, StreamExt};
+use hyper::{Body, StatusCode};
+use serde::Deserialize;
+use std::{io, sync::Arc};
+use trek_core::{Context, NdJsonStream, Rejection};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Record {
+    a: u32,
+}
+
+fn context(content_type: &str, chunks: &[&'static str]) -> Context<()> {
+    let chunks: Vec<io::Result<&'static str>> = chunks.iter().map(|c| Ok(*c)).collect();
+    let req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("content-type", content_type)
+        .body(Body::wrap_stream(stream::iter(chunks)))
+        .unwrap();
+    Context::new(Arc::new(()), req, vec![], vec![])
+}
+
+fn records(chunks: &[&'static str]) -> NdJsonStream<Record> {
+    context("application/x-ndjson", chunks).ndjson().unwrap()
+}
+
+fn collect(s: NdJsonStream<Record>) -> Vec<Result<Record, Rejection>> {
+    block_on(s.map(|r| r.map_err(Rejection::from)).collect())
+}
+
+#[test]
+fn lines_split_across_chunks() {
+    let items = collect(records(&["{\"a\":", "1}\n{\"a\"", ":2}\n\n", "{\"a\":3}"]));
+    let items: Vec<_> = items.into_iter().map(Result::unwrap).collect();
+    assert_eq!(
+        items,
+        vec![Record { a: 1 }, Record { a: 2 }, Record { a: 3 }]
+    );
+}
+
+#[test]
+fn bad_records_do_not_end_the_stream() {
+    let items = collect(records(&["{\"a\":1}\r\nnope\n{\"a\":2}\n"]));
+    assert_eq!(items.len(), 3);
+    assert!(items[1].is_err());
+    assert_eq!(items[2].as_ref().unwrap(), &Record { a: 2 });
+}
+
+#[test]
+fn oversize_line_ends_the_stream() {
+    let items = collect(records(&["{\"a\":1}\n{\"a\":", "1", "1111111111"]).max_line(10));
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].as_ref().unwrap(), &Record { a: 1 });
+    match &items[1] {
+        Err(Rejection::LineTooLong(10)) => {}
+        r => panic!("expected LineTooLong, got {:?}", r),
+    }
+
+    let items = collect(records(&["{\"a\":1111111111}\n{\"a\":1}\n"]).max_line(10));
+    assert_eq!(items.len(), 1);
+    assert_eq!(
+        items[0].as_ref().unwrap_err().status(),
+        StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
+
+#[test]
+fn body_limit_applies_per_line() {
+    let mut cx = context("application/x-ndjson", &["{\"a\":1}\n"; 8]);
+    cx.set_body_limit(10);
+    let items = collect(cx.ndjson().unwrap());
+    assert_eq!(items.len(), 8);
+    assert!(items.iter().all(Result::is_ok));
+}
+
+#[test]
+fn total_limit() {
+    let items = collect(records(&["{\"a\":1}\n", "{\"a\":2}\n", "{\"a\":3}\n"]).limit(12));
+    assert_eq!(items.len(), 2);
+    assert!(items[0].is_ok());
+    match &items[1] {
+        Err(Rejection::PayloadTooLarge(12)) => {}
+        r => panic!("expected PayloadTooLarge, got {:?}", r),
+    }
+}
+
+#[test]
+fn content_type_is_checked() {
+    let mut cx = context("application/json", &["{\"a\":1}\n"]);
+    let e = Rejection::from(cx.ndjson::<Record>().err().unwrap());
+    assert_eq!(e.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
@@ -0,0 +1,125 @@
+use hyper::header::{ACCEPT, VARY};
+use std::sync::Arc;
+use trek_core::{Body, Context, IntoResponse, Negotiate, StatusCode};
+
+const JSON: &str = "application/json";
+const HTML: &str = "text/html";
+
+fn context(accept: Option<&str>) -> Context<()> {
+    let mut req = http::Request::builder();
+    if let Some(accept) = accept {
+        req = req.header(ACCEPT, accept);
+    }
+    Context::new(
+        Arc::new(()),
+        req.body(Body::empty()).unwrap(),
+        vec![],
+        vec![],
+    )
+}
+
+fn best<'a>(accept: Option<&str>, offers: &[&'a str]) -> Option<&'a str> {
+    context(accept).accepts(offers)
+}
+
+#[test]
+fn no_accept_takes_the_first_offer() {
+    assert_eq!(best(None, &[JSON, HTML]), Some(JSON));
+    assert_eq!(best(Some(" "), &[HTML, JSON]), Some(HTML));
+    assert_eq!(best(None, &[]), None);
+}
+
+#[test]
+fn q_values() {
+    assert_eq!(
+        best(Some("text/html;q=0.5, application/json"), &[HTML, JSON]),
+        Some(JSON)
+    );
+    assert_eq!(
+        best(
+            Some("text/html;q=0.9, application/json;q=0.8"),
+            &[JSON, HTML]
+        ),
+        Some(HTML)
+    );
+    assert_eq!(
+        best(Some("application/json; q=1.0"), &[HTML, JSON]),
+        Some(JSON)
+    );
+}
+
+#[test]
+fn wildcards() {
+    assert_eq!(best(Some("*/*"), &[HTML, JSON]), Some(HTML));
+    assert_eq!(best(Some("text/*"), &[JSON, HTML]), Some(HTML));
+    assert_eq!(
+        best(Some("text/*;q=0.5, */*;q=0.1"), &[JSON, HTML]),
+        Some(HTML)
+    );
+    assert_eq!(best(Some("image/*"), &[JSON, HTML]), None);
+}
+
+#[test]
+fn most_specific_range_wins() {
+    assert_eq!(
+        best(Some("application/json;q=0, */*"), &[JSON, HTML]),
+        Some(HTML)
+    );
+    assert_eq!(best(Some("text/*;q=0, text/html"), &[HTML]), Some(HTML));
+    assert_eq!(
+        best(Some("text/html;q=0.2, text/*;q=0.8"), &["text/plain", HTML]),
+        Some("text/plain")
+    );
+}
+
+#[test]
+fn q_zero_is_not_acceptable() {
+    assert_eq!(best(Some("application/json;q=0"), &[JSON]), None);
+    assert_eq!(best(Some("*/*;q=0"), &[JSON, HTML]), None);
+}
+
+#[test]
+fn ties_go_to_the_earlier_offer() {
+    assert_eq!(
+        best(Some("text/html, application/json"), &[JSON, HTML]),
+        Some(JSON)
+    );
+    assert_eq!(best(Some("*/*"), &[JSON, HTML]), Some(JSON));
+}
+
+#[test]
+fn malformed_ranges_are_skipped() {
+    assert_eq!(
+        best(Some("text/html;q=2, application/json;q=0.1"), &[HTML, JSON]),
+        Some(JSON)
+    );
+    assert_eq!(best(Some("text/html;q=x, , json"), &[HTML]), None);
+    assert_eq!(
+        best(Some("TEXT/HTML"), &["text/html; charset=utf-8"]),
+        Some("text/html; charset=utf-8")
+    );
+}
+
+#[test]
+fn context_accepts() {
+    let cx = context(Some("text/html;q=0.9, application/json"));
+    assert_eq!(cx.accepts(&[HTML, JSON]), Some(JSON));
+    assert_eq!(cx.accepts(&["text/plain"]), None);
+}
+
+#[test]
+fn negotiate_not_acceptable() {
+    let cx = context(Some("image/png"));
+    let res = Negotiate::new(&cx, 1)
+        .text(|v| v.to_string())
+        .into_response();
+    assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(res.headers()[VARY], "accept");
+
+    let cx = context(Some("text/plain"));
+    let res = Negotiate::new(&cx, 1)
+        .text(|v| v.to_string())
+        .into_response();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()[VARY], "accept");
+}
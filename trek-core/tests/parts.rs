@@ -0,0 +1,32 @@
+use futures::{executor::block_on, future::BoxFuture};
+use hyper::Body;
+use std::sync::Arc;
+use trek_core::{debug::RouteParams, Context, Middleware, Response};
+
+fn handler(cx: Context<()>) -> BoxFuture<'static, Response> {
+    Box::pin(async move {
+        assert_eq!(cx.headers()["authorization"], "secret");
+        Response::new(Body::empty())
+    })
+}
+
+#[test]
+fn parts_are_handed_over_on_drop() {
+    let req = http::Request::builder()
+        .uri("/users/7?x=1")
+        .header("authorization", "secret")
+        .body(Body::empty())
+        .unwrap();
+    let middleware: Vec<Arc<dyn Middleware<Context<()>>>> = vec![Arc::new(handler)];
+    let params = vec![("id".to_owned(), "7".to_owned())];
+    let mut cx = Context::new(Arc::new(()), req, params.clone(), middleware);
+    let mut parts = cx.parts_on_drop();
+    assert!(parts.try_recv().unwrap().is_none());
+
+    block_on(cx.next());
+    let parts = parts.try_recv().unwrap().unwrap();
+    assert_eq!(parts.uri, "/users/7?x=1");
+    assert_eq!(parts.headers["authorization"], "secret");
+    let RouteParams(route_params) = parts.extensions.get::<RouteParams>().unwrap();
+    assert_eq!(route_params, &params);
+}
@@ -0,0 +1,122 @@
+use futures::executor::block_on;
+use http::request::Parts;
+use hyper::{body::to_bytes, header::CONTENT_TYPE};
+use serde_json::{json, Value};
+use std::io;
+use trek_core::{problem_handler, Error, ErrorResponse, Problem, Rejection, Response, StatusCode};
+
+fn parts(uri: &str) -> Parts {
+    http::Request::builder()
+        .uri(uri)
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0
+}
+
+fn document(res: Response) -> (StatusCode, Value) {
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/problem+json");
+    let status = res.status();
+    let body = block_on(to_bytes(res.into_body())).unwrap();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn new_is_about_blank() {
+    assert_eq!(
+        document(Problem::new(StatusCode::NOT_FOUND).error_response()),
+        (
+            StatusCode::NOT_FOUND,
+            json!({ "type": "about:blank", "title": "Not Found", "status": 404 })
+        )
+    );
+}
+
+#[test]
+fn fields_and_extensions() {
+    let problem = Problem::new(StatusCode::FORBIDDEN)
+        .with_type("https://example.com/probs/out-of-credit")
+        .title("You do not have enough credit.")
+        .detail("Your current balance is 30, but that costs 50.")
+        .instance("/account/12345/msgs/abc")
+        .extension("balance", 30)
+        .extension("status", 200);
+    assert_eq!(problem.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        problem.to_string(),
+        "You do not have enough credit.: Your current balance is 30, but that costs 50."
+    );
+    assert_eq!(
+        document(problem.render_response()),
+        (
+            StatusCode::FORBIDDEN,
+            json!({
+                "type": "https://example.com/probs/out-of-credit",
+                "title": "You do not have enough credit.",
+                "status": 403,
+                "detail": "Your current balance is 30, but that costs 50.",
+                "instance": "/account/12345/msgs/abc",
+                "balance": 30,
+            })
+        )
+    );
+}
+
+#[test]
+fn handler_keeps_the_status_of_client_errors() {
+    let err = Error::from(Rejection::PayloadTooLarge(10));
+    assert_eq!(
+        document(problem_handler(err, &parts("/upload?x=1"))),
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            json!({
+                "type": "about:blank",
+                "title": "Payload Too Large",
+                "status": 413,
+                "detail": "payload too large: the limit is 10 bytes",
+                "instance": "/upload",
+            })
+        )
+    );
+}
+
+#[test]
+fn handler_hides_the_detail_of_server_errors() {
+    let err = Error::from(io::Error::new(
+        io::ErrorKind::Other,
+        "db password is hunter2",
+    ));
+    assert_eq!(
+        document(problem_handler(err, &parts("/users"))),
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            json!({
+                "type": "about:blank",
+                "title": "Internal Server Error",
+                "status": 500,
+                "instance": "/users",
+            })
+        )
+    );
+}
+
+#[test]
+fn handler_passes_problems_through() {
+    let problem = Problem::new(StatusCode::CONFLICT)
+        .detail("taken")
+        .instance("/users/ann");
+    let err = Error::from(problem);
+    assert_eq!(
+        document(problem_handler(err, &parts("/users"))),
+        (
+            StatusCode::CONFLICT,
+            json!({
+                "type": "about:blank",
+                "title": "Conflict",
+                "status": 409,
+                "detail": "taken",
+                "instance": "/users/ann",
+            })
+        )
+    );
+}
@@ -0,0 +1,162 @@
+use futures::executor::block_on;
+use hyper::{body::to_bytes, header, Body};
+use serde::Deserialize;
+use std::{io, sync::Arc, time::Duration};
+use trek_core::{Context, ErrorResponse, Rejection, StatusCode};
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Post {
+    user_id: u64,
+    post_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct User {
+    name: String,
+    age: u8,
+}
+
+fn context(params: Vec<(&str, &str)>, body: &'static str) -> Context<()> {
+    let req = http::Request::builder()
+        .method("POST")
+        .uri("/")
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap();
+    let params = params
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect();
+    Context::new(Arc::new(()), req, params, vec![])
+}
+
+fn json_rejection(body: &'static str) -> Rejection {
+    block_on(context(vec![], body).json::<User>())
+        .unwrap_err()
+        .into()
+}
+
+#[test]
+fn missing_param() {
+    let e: Rejection = context(vec![("user_id", "1")], "")
+        .params::<Post>()
+        .unwrap_err()
+        .into();
+    match &e {
+        Rejection::MissingParam(name) => assert_eq!(name, "post_id"),
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn invalid_param() {
+    let e: Rejection = context(vec![("user_id", "1"), ("post_id", "x")], "")
+        .params::<Post>()
+        .unwrap_err()
+        .into();
+    match &e {
+        Rejection::InvalidParam(_) => {}
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn json_syntax_error_is_bad_request() {
+    let e = json_rejection("{\n  \"name\": \"ann\",\n  \"age\": }");
+    match &e {
+        Rejection::InvalidJson {
+            line,
+            column,
+            syntax,
+            ..
+        } => {
+            assert_eq!((*line, *column), (3, 10));
+            assert!(*syntax);
+        }
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn json_eof_is_bad_request() {
+    let e = json_rejection(r#"{"name":"ann""#);
+    assert_eq!(e.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn json_data_error_is_unprocessable() {
+    let e = json_rejection("{\"name\": \"ann\",\n\"age\": \"thirty\"}");
+    match &e {
+        Rejection::InvalidJson {
+            line,
+            column,
+            syntax,
+            ..
+        } => {
+            assert_eq!((*line, *column), (2, 15));
+            assert!(!*syntax);
+        }
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    assert_eq!(e.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[test]
+fn statuses() {
+    let cases = vec![
+        (Rejection::InvalidQuery("x".to_owned()), 400),
+        (Rejection::InvalidForm("x".to_owned()), 400),
+        (Rejection::InvalidBody("x".to_owned()), 400),
+        (Rejection::InvalidUpgrade("x"), 400),
+        (Rejection::PayloadTooLarge(1), 413),
+        (Rejection::LineTooLong(1), 413),
+        (Rejection::BodyTimeout(Duration::from_secs(1)), 408),
+        (Rejection::UnsupportedMediaType("application/json"), 415),
+        (Rejection::UnsupportedWebSocketVersion, 426),
+        (Rejection::MissingExtension("u8"), 500),
+        (Rejection::BodyTaken, 500),
+    ];
+    for (e, status) in cases {
+        assert_eq!(e.status().as_u16(), status, "{:?}", e);
+        assert_eq!(e.error_response().status().as_u16(), status, "{:?}", e);
+        assert_eq!(ErrorResponse::status(&e).as_u16(), status, "{:?}", e);
+        let e = io::Error::from(e);
+        assert_eq!(ErrorResponse::status(&e).as_u16(), status, "{:?}", e);
+    }
+}
+
+#[test]
+fn error_response() {
+    let res = Rejection::MissingParam("id".to_owned()).error_response();
+    assert_eq!(
+        res.headers()[header::CONTENT_TYPE],
+        "text/plain; charset=utf-8"
+    );
+    let body = block_on(to_bytes(res.into_body())).unwrap();
+    assert_eq!(&body[..], b"missing path param `id`");
+
+    let res = Rejection::UnsupportedWebSocketVersion.error_response();
+    assert_eq!(res.headers()[header::SEC_WEBSOCKET_VERSION], "13");
+}
+
+#[test]
+fn round_trips_through_io_error() {
+    let e: io::Error = Rejection::BodyTimeout(Duration::from_secs(1)).into();
+    assert_eq!(e.kind(), io::ErrorKind::TimedOut);
+    let e: io::Error = Rejection::PayloadTooLarge(1).into();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    match Rejection::from(e) {
+        Rejection::PayloadTooLarge(1) => {}
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+    let e = Rejection::from(io::Error::new(io::ErrorKind::Other, "boom"));
+    match e {
+        Rejection::InvalidBody(message) => assert_eq!(message, "boom"),
+        e => panic!("unexpected rejection: {:?}", e),
+    }
+}
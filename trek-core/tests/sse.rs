@@ -0,0 +1,99 @@
+use futures::{executor::block_on, stream, StreamExt};
+use hyper::{
+    body::to_bytes,
+    header::{CACHE_CONTROL, CONTENT_TYPE},
+};
+use serde::Serialize;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use trek_core::{
+    sse::{Event, Sse},
+    IntoResponse, Response,
+};
+
+fn body(res: Response) -> String {
+    let body = block_on(to_bytes(res.into_body())).unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+fn frames(events: Vec<Event>) -> String {
+    body(Sse::new(stream::iter(events)).into_response())
+}
+
+#[test]
+fn headers() {
+    let res = Sse::new(stream::iter(vec![])).into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "text/event-stream");
+    assert_eq!(res.headers()[CACHE_CONTROL], "no-cache");
+}
+
+#[test]
+fn multi_line_data_is_split() {
+    assert_eq!(
+        frames(vec![Event::default().data("one\ntwo\r\nthree")]),
+        "data: one\ndata: two\ndata: three\n\n"
+    );
+}
+
+#[test]
+fn fields() {
+    let event = Event::default()
+        .event("update")
+        .data("hi")
+        .id("7")
+        .retry(Duration::from_secs(3));
+    assert_eq!(
+        frames(vec![event]),
+        "event: update\ndata: hi\nid: 7\nretry: 3000\n\n"
+    );
+}
+
+#[test]
+fn id_and_event_are_single_line() {
+    let event = Event::default().event("up\ndate").id("1\r\n2");
+    assert_eq!(frames(vec![event]), "event: update\nid: 12\n\n");
+}
+
+#[test]
+fn json_data_and_comments() {
+    #[derive(Serialize)]
+    struct Update {
+        n: u32,
+    }
+
+    let events = vec![
+        Event::default().comment("hello"),
+        Event::default().json(&Update { n: 1 }),
+    ];
+    assert_eq!(frames(events), ": hello\n\ndata: {\"n\":1}\n\n");
+}
+
+#[test]
+fn keep_alive_comment() {
+    let res = Sse::new(stream::pending())
+        .keep_alive(Some(Duration::from_millis(10)))
+        .into_response();
+    let mut body = res.into_body();
+    for _ in 0..2 {
+        let chunk = block_on(body.next()).unwrap().unwrap();
+        assert_eq!(&chunk[..], b":\n\n");
+    }
+}
+
+#[test]
+fn on_close_runs_when_the_stream_ends() {
+    let closed = Arc::new(AtomicBool::new(false));
+    let flag = closed.clone();
+    let res = Sse::new(stream::iter(vec![Event::default().data("bye")]))
+        .keep_alive(None)
+        .on_close(move || flag.store(true, Ordering::SeqCst))
+        .into_response();
+    assert!(!closed.load(Ordering::SeqCst));
+    assert_eq!(body(res), "data: bye\n\n");
+    assert!(closed.load(Ordering::SeqCst));
+}
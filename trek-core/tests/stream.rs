@@ -0,0 +1,117 @@
+use futures::{executor::block_on, io::Cursor, stream, StreamExt};
+use hyper::header::CONTENT_TYPE;
+use serde::{ser, Serialize, Serializer};
+use std::io;
+use trek_core::{
+    stream::{from_reader, from_stream, NdJson},
+    IntoResponse, Response,
+};
+
+/// The chunks of the body, and whether it ended with an error.
+fn chunks(res: Response) -> (Vec<String>, bool) {
+    block_on(async {
+        let mut body = res.into_body();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = body.next().await {
+            match chunk {
+                Ok(chunk) => chunks.push(String::from_utf8(chunk.to_vec()).unwrap()),
+                Err(_) => return (chunks, true),
+            }
+        }
+        (chunks, false)
+    })
+}
+
+#[test]
+fn from_stream_sends_each_item() {
+    let items: Vec<io::Result<&'static str>> = vec![Ok("a,b\n"), Ok("1,2\n")];
+    let res = from_stream(stream::iter(items))
+        .content_type("text/csv")
+        .into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "text/csv");
+    assert_eq!(
+        chunks(res),
+        (vec!["a,b\n".to_owned(), "1,2\n".to_owned()], false)
+    );
+}
+
+#[test]
+fn from_stream_defaults_to_octet_stream() {
+    let items: Vec<io::Result<Vec<u8>>> = vec![];
+    let res = from_stream(stream::iter(items)).into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/octet-stream");
+    assert_eq!(chunks(res), (vec![], false));
+}
+
+#[test]
+fn error_mid_stream_aborts_the_body() {
+    let items = vec![
+        Ok("first"),
+        Err(io::Error::new(io::ErrorKind::Other, "gone")),
+        Ok("never"),
+    ];
+    let res = from_stream(stream::iter(items)).into_response();
+    assert_eq!(chunks(res), (vec!["first".to_owned()], true));
+}
+
+#[test]
+fn from_reader_reads_in_chunks() {
+    let res = from_reader(Cursor::new(b"hello world".to_vec()))
+        .chunk_size(4)
+        .into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/octet-stream");
+    assert_eq!(
+        chunks(res),
+        (
+            vec!["hell".to_owned(), "o wo".to_owned(), "rld".to_owned()],
+            false
+        )
+    );
+}
+
+#[test]
+fn from_reader_chunk_size_is_at_least_one() {
+    let res = from_reader(Cursor::new(b"ab".to_vec()))
+        .chunk_size(0)
+        .into_response();
+    assert_eq!(chunks(res), (vec!["a".to_owned(), "b".to_owned()], false));
+}
+
+#[derive(Serialize)]
+struct Row {
+    id: u32,
+}
+
+#[test]
+fn ndjson_writes_a_line_per_item() {
+    let res = NdJson(stream::iter(vec![Row { id: 1 }, Row { id: 2 }])).into_response();
+    assert_eq!(res.headers()[CONTENT_TYPE], "application/x-ndjson");
+    assert_eq!(
+        chunks(res),
+        (
+            vec!["{\"id\":1}\n".to_owned(), "{\"id\":2}\n".to_owned()],
+            false
+        )
+    );
+}
+
+enum Item {
+    Row(u32),
+    Broken,
+}
+
+impl Serialize for Item {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Item::Row(id) => Row { id: *id }.serialize(serializer),
+            Item::Broken => Err(ser::Error::custom("broken")),
+        }
+    }
+}
+
+#[test]
+fn ndjson_serialize_error_aborts_the_body() {
+    let items = vec![Item::Row(1), Item::Broken, Item::Row(3)];
+    let res = NdJson(stream::iter(items)).into_response();
+    assert_eq!(chunks(res), (vec!["{\"id\":1}\n".to_owned()], true));
+}
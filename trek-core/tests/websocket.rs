@@ -0,0 +1,129 @@
+#![cfg(feature = "websocket")]
+
+use futures::executor::block_on;
+use hyper::{
+    header::{
+        HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_PROTOCOL,
+        SEC_WEBSOCKET_VERSION, UPGRADE,
+    },
+    Method, StatusCode,
+};
+use std::sync::Arc;
+use trek_core::{
+    websocket::{self, WebSocketUpgrade},
+    Context, ErrorResponse, Rejection, Request,
+};
+
+fn upgrade(req: Request) -> Result<WebSocketUpgrade, Rejection> {
+    let mut cx = Context::new(Arc::new(()), req, vec![], vec![]);
+    cx.websocket().map_err(Rejection::from)
+}
+
+fn rejection(edit: impl FnOnce(&mut Request)) -> Rejection {
+    let mut req = websocket::request("/", &[]);
+    edit(&mut req);
+    upgrade(req).unwrap_err()
+}
+
+#[test]
+fn switching_protocols() {
+    let ws = upgrade(websocket::request("/chat", &[])).unwrap();
+    assert_eq!(ws.protocol(), None);
+
+    let (res, _) = ws.upgrade();
+    assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+    assert_eq!(res.headers()[CONNECTION], "upgrade");
+    assert_eq!(res.headers()[UPGRADE], "websocket");
+    assert_eq!(
+        res.headers()[SEC_WEBSOCKET_ACCEPT],
+        websocket::SAMPLE_ACCEPT
+    );
+    assert!(res.headers().get(SEC_WEBSOCKET_PROTOCOL).is_none());
+}
+
+#[test]
+fn upgrade_without_connection_fails() {
+    let (_, ws) = upgrade(websocket::request("/chat", &[])).unwrap().upgrade();
+    assert!(block_on(ws).is_err());
+}
+
+#[test]
+fn header_tokens_ignore_case_and_lists() {
+    let mut req = websocket::request("/", &[]);
+    let headers = req.headers_mut();
+    headers.insert(CONNECTION, HeaderValue::from_static("keep-alive, Upgrade"));
+    headers.insert(UPGRADE, HeaderValue::from_static("WebSocket"));
+    assert!(upgrade(req).is_ok());
+}
+
+#[test]
+fn invalid_upgrades() {
+    let rejections = vec![
+        rejection(|req| *req.method_mut() = Method::POST),
+        rejection(|req| {
+            req.headers_mut().remove(CONNECTION);
+        }),
+        rejection(|req| {
+            req.headers_mut()
+                .insert(UPGRADE, HeaderValue::from_static("h2c"));
+        }),
+        rejection(|req| {
+            req.headers_mut().remove(SEC_WEBSOCKET_KEY);
+        }),
+    ];
+    for rejection in rejections {
+        match &rejection {
+            Rejection::InvalidUpgrade(_) => {}
+            _ => panic!("expected an invalid upgrade, got {:?}", rejection),
+        }
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[test]
+fn unsupported_version() {
+    let missing = rejection(|req| {
+        req.headers_mut().remove(SEC_WEBSOCKET_VERSION);
+    });
+    let old = rejection(|req| {
+        req.headers_mut()
+            .insert(SEC_WEBSOCKET_VERSION, HeaderValue::from_static("8"));
+    });
+    for rejection in &[missing, old] {
+        let res = rejection.error_response();
+        assert_eq!(res.status(), StatusCode::UPGRADE_REQUIRED);
+        assert_eq!(res.headers()[SEC_WEBSOCKET_VERSION], "13");
+    }
+}
+
+#[test]
+fn subprotocol_selection() {
+    let req = websocket::request("/", &["chat.v2", "chat.v1"]);
+    let ws = upgrade(req).unwrap();
+    assert_eq!(ws.requested_protocols(), &["chat.v2", "chat.v1"]);
+
+    let ws = ws.protocols(&["chat.v1", "chat.v2"]);
+    assert_eq!(ws.protocol(), Some("chat.v2"));
+    let (res, _) = ws.upgrade();
+    assert_eq!(res.headers()[SEC_WEBSOCKET_PROTOCOL], "chat.v2");
+
+    let req = websocket::request("/", &["chat.v2", "chat.v1"]);
+    let ws = upgrade(req).unwrap().protocols(&["chat.v1"]);
+    assert_eq!(ws.protocol(), Some("chat.v1"));
+
+    let req = websocket::request("/", &["chat.v3"]);
+    let ws = upgrade(req).unwrap().protocols(&["chat.v1"]);
+    assert_eq!(ws.protocol(), None);
+    let (res, _) = ws.upgrade();
+    assert!(res.headers().get(SEC_WEBSOCKET_PROTOCOL).is_none());
+}
+
+#[test]
+fn protocols_across_headers() {
+    let mut req = websocket::request("/", &[]);
+    let headers = req.headers_mut();
+    headers.append(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static("a, b"));
+    headers.append(SEC_WEBSOCKET_PROTOCOL, HeaderValue::from_static(" c,"));
+    let ws = upgrade(req).unwrap();
+    assert_eq!(ws.requested_protocols(), &["a", "b", "c"]);
+}
@@ -0,0 +1,88 @@
+//! REST controllers.
+
+use http::Method;
+use trek_core::BoxDynHandler;
+
+use crate::Resources;
+
+/// Handlers of a REST resource, registered with `Router::resources_with`.
+///
+/// An action is routed when its method returns a handler. The actions left
+/// to the default `None` are not routed, requests to a routed path with
+/// their method answer `405` with an `Allow` header listing the others.
+/// `update` answers both `PUT` and `PATCH`.
+///
+/// ```ignore
+/// #[derive(Clone)]
+/// struct UsersController {
+///     db: Db,
+/// }
+///
+/// impl ResourceController<Context<State>> for UsersController {
+///     fn index(&self) -> Option<BoxDynHandler<Context<State>>> {
+///         let db = self.db.clone();
+///         Some(into_box_dyn_handler(move |_: Context<State>| {
+///             let db = db.clone();
+///             async move { db.users().await }
+///         }))
+///     }
+///
+///     fn show(&self) -> Option<BoxDynHandler<Context<State>>> {
+///         Some(into_box_dyn_handler(|cx: Context<State>| async move {
+///             format!("user {:?}", cx.params::<u64>())
+///         }))
+///     }
+/// }
+///
+/// router.resources_with("users", UsersController { db });
+/// ```
+pub trait ResourceController<Context>: Send + Sync + 'static {
+    fn index(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn show(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn create(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn update(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn delete(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn new(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+
+    fn edit(&self) -> Option<BoxDynHandler<Context>> {
+        None
+    }
+}
+
+/// The actions the controller has a handler for.
+pub(crate) fn into_maps<Context, C>(controller: &C) -> Vec<(Resources, BoxDynHandler<Context>)>
+where
+    C: ResourceController<Context>,
+{
+    let update = controller.update();
+    vec![
+        (Resources::Index, controller.index()),
+        (Resources::Create, controller.create()),
+        (Resources::New, controller.new()),
+        (Resources::Show, controller.show()),
+        (Resources::Update(Method::PUT), update.clone()),
+        (Resources::Update(Method::PATCH), update),
+        (Resources::Delete, controller.delete()),
+        (Resources::Edit, controller.edit()),
+    ]
+    .into_iter()
+    .filter_map(|(action, handler)| handler.map(|handler| (action, handler)))
+    .collect()
+}
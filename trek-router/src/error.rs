@@ -0,0 +1,76 @@
+//! Route registration errors.
+
+use http::Method;
+use std::{error, fmt};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteError {
+    /// The same method and pattern was registered twice.
+    Duplicate {
+        method: Method,
+        existing: String,
+        route: String,
+    },
+    /// An earlier route accepts every path the new route would match, so the
+    /// new route can never be reached.
+    Ambiguous {
+        method: Method,
+        existing: String,
+        route: String,
+    },
+    /// Both routes match some paths, the path tree prefers the one with
+    /// more static segments.
+    Overlap {
+        method: Method,
+        existing: String,
+        route: String,
+    },
+    /// The pattern could not be parsed.
+    InvalidPattern { pattern: String, reason: String },
+    /// A scope resolves to the path of its parent.
+    InvalidScope { parent: String, scope: String },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate {
+                method,
+                existing,
+                route,
+            } => write!(
+                f,
+                "duplicate route: {} `{}` is already registered as `{}`",
+                method, route, existing
+            ),
+            Self::Ambiguous {
+                method,
+                existing,
+                route,
+            } => write!(
+                f,
+                "ambiguous route: {} `{}` is shadowed by `{}`",
+                method, route, existing
+            ),
+            Self::Overlap {
+                method,
+                existing,
+                route,
+            } => write!(
+                f,
+                "overlapping routes: {} `{}` matches some paths of `{}`",
+                method, route, existing
+            ),
+            Self::InvalidPattern { pattern, reason } => {
+                write!(f, "invalid route pattern `{}`: {}", pattern, reason)
+            }
+            Self::InvalidScope { parent, scope } => write!(
+                f,
+                "invalid scope: scope path `{}` equals parent path `{}`",
+                scope, parent
+            ),
+        }
+    }
+}
+
+impl error::Error for RouteError {}
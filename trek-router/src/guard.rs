@@ -0,0 +1,75 @@
+//! Route guards.
+//!
+//! A guard is a predicate over the request. Guarded routes only match when
+//! all of their guards pass, otherwise the next route registered on the same
+//! path is tried.
+
+use http::header::{HeaderName, CONTENT_TYPE};
+use percent_encoding::percent_decode_str;
+use std::borrow::Cow;
+use trek_core::Request;
+
+pub trait Guard: Send + Sync + 'static {
+    fn check(&self, req: &Request) -> bool;
+}
+
+impl<F> Guard for F
+where
+    F: Send + Sync + 'static + Fn(&Request) -> bool,
+{
+    fn check(&self, req: &Request) -> bool {
+        (self)(req)
+    }
+}
+
+/// Matches when the header equals `value`, ignoring ASCII case.
+///
+/// Panics if `name` is not a valid header name.
+pub fn header(name: &'static str, value: &'static str) -> impl Guard {
+    let name = HeaderName::from_bytes(name.as_bytes()).expect("invalid header name");
+    move |req: &Request| {
+        req.headers()
+            .get(&name)
+            .and_then(|v| v.to_str().ok())
+            .map_or(false, |v| v.eq_ignore_ascii_case(value))
+    }
+}
+
+/// Matches the media type of `content-type`, ignoring parameters such as
+/// `charset`.
+pub fn content_type(mime: &'static str) -> impl Guard {
+    move |req: &Request| {
+        req.headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map_or(false, |v| v.trim().eq_ignore_ascii_case(mime))
+    }
+}
+
+/// Matches when the query string has `name=value`, comparing the decoded
+/// name and value.
+pub fn query(name: &'static str, value: &'static str) -> impl Guard {
+    move |req: &Request| {
+        req.uri().query().map_or(false, |q| {
+            q.split('&').any(|pair| {
+                pair.splitn(2, '=')
+                    .map(decode)
+                    .eq([name, value].iter().copied())
+            })
+        })
+    }
+}
+
+/// Decodes a component of `application/x-www-form-urlencoded` query.
+fn decode(s: &str) -> Cow<'_, str> {
+    if s.contains('+') {
+        Cow::Owned(
+            percent_decode_str(&s.replace('+', " "))
+                .decode_utf8_lossy()
+                .into_owned(),
+        )
+    } else {
+        percent_decode_str(s).decode_utf8_lossy()
+    }
+}
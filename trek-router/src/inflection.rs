@@ -0,0 +1,131 @@
+//! Inflection of resource names.
+//!
+//! Inflector's rules mangle words such as `news`, `status` or `data`, so
+//! irregular and uncountable words are looked up here first.
+
+use inflector::string::{pluralize::to_plural, singularize::to_singular};
+
+#[derive(Debug, Clone)]
+pub struct Inflections {
+    irregular: Vec<(String, String)>,
+    uncountable: Vec<String>,
+}
+
+impl Default for Inflections {
+    fn default() -> Self {
+        let mut inflections = Self::empty();
+        inflections
+            .irregular("person", "people")
+            .irregular("child", "children")
+            .irregular("status", "statuses")
+            .irregular("alias", "aliases")
+            .irregular("axis", "axes")
+            .uncountable("news")
+            .uncountable("data")
+            .uncountable("metadata")
+            .uncountable("information")
+            .uncountable("equipment")
+            .uncountable("series")
+            .uncountable("species")
+            .uncountable("sheep")
+            .uncountable("fish");
+        inflections
+    }
+}
+
+impl Inflections {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Without any irregular or uncountable words.
+    pub fn empty() -> Self {
+        Self {
+            irregular: Vec::new(),
+            uncountable: Vec::new(),
+        }
+    }
+
+    pub fn irregular(&mut self, singular: &str, plural: &str) -> &mut Self {
+        let singular = singular.to_lowercase();
+        self.irregular.retain(|(s, _)| *s != singular);
+        self.irregular.push((singular, plural.to_lowercase()));
+        self
+    }
+
+    pub fn uncountable(&mut self, word: &str) -> &mut Self {
+        let word = word.to_lowercase();
+        if !self.uncountable.contains(&word) {
+            self.uncountable.push(word);
+        }
+        self
+    }
+
+    /// Singularizes the last segment of `path`.
+    pub fn singularize(&self, path: &str) -> String {
+        self.inflect(path, |word| {
+            let lower = word.to_lowercase();
+            if self.uncountable.contains(&lower) {
+                return word.to_owned();
+            }
+            for (singular, plural) in &self.irregular {
+                if lower == *plural {
+                    return with_case_of(word, singular);
+                }
+                if lower == *singular {
+                    return word.to_owned();
+                }
+            }
+            to_singular(word)
+        })
+    }
+
+    /// Pluralizes the last segment of `path`.
+    pub fn pluralize(&self, path: &str) -> String {
+        self.inflect(path, |word| {
+            let lower = word.to_lowercase();
+            if self.uncountable.contains(&lower) {
+                return word.to_owned();
+            }
+            for (singular, plural) in &self.irregular {
+                if lower == *singular {
+                    return with_case_of(word, plural);
+                }
+                if lower == *plural {
+                    return word.to_owned();
+                }
+            }
+            to_plural(word)
+        })
+    }
+
+    fn inflect(&self, path: &str, f: impl Fn(&str) -> String) -> String {
+        let (prefix, word) = match path.rfind('/') {
+            Some(i) => path.split_at(i + 1),
+            None => ("", path),
+        };
+        if word.is_empty() {
+            return path.to_owned();
+        }
+        prefix.to_owned() + &f(word)
+    }
+}
+
+/// Capitalizes or uppercases `inflected` like `word`, so `People` gives
+/// `Person` and `STATUS` gives `STATUSES`.
+fn with_case_of(word: &str, inflected: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) if first.is_uppercase() => {
+            if word.chars().count() > 1 && chars.all(|c| !c.is_lowercase()) {
+                inflected.to_uppercase()
+            } else {
+                let mut chars = inflected.chars();
+                chars
+                    .next()
+                    .map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+            }
+        }
+        _ => inflected.to_owned(),
+    }
+}
@@ -3,25 +3,279 @@ extern crate log;
 
 use fxhash::FxHashMap;
 use http::Method;
-use inflector::string::{pluralize::to_plural, singularize::to_singular};
 use path_tree::PathTree;
 use std::{fmt, sync::Arc};
 
 use trek_core::{
     box_dyn_handler_into_middleware, into_box_dyn_handler, BoxDynHandler, Handler, Middleware,
+    Request,
 };
 
+mod controller;
+mod error;
+pub mod guard;
+mod inflection;
+mod normalize;
 mod resource;
+mod route;
 
-pub use resource::{Resource, Resources};
+pub use controller::ResourceController;
+pub use error::RouteError;
+pub use guard::Guard;
+pub use inflection::Inflections;
+pub use normalize::{decode, normalize, TrailingSlash};
+pub use resource::{Resource, Resources, ResourcesBuilder};
+pub use route::{Constraint, Pattern};
 
-pub(crate) type VecMiddleware<Context> = Vec<Arc<dyn Middleware<Context>>>;
+pub(crate) use route::{Route, VecMiddleware};
 
-pub(crate) type Trees<Context> = FxHashMap<Method, PathTree<VecMiddleware<Context>>>;
+use normalize::{decode_value, encode, toggle_trailing_slash};
+use resource::with_param;
+
+pub(crate) type Trees<Context> = FxHashMap<Method, Tree<Context>>;
+
+/// Routes of one method. Routes whose patterns only differ by parameter names
+/// or constraints share a node of the path tree and are tried in order.
+pub(crate) struct Tree<Context> {
+    paths: PathTree<usize>,
+    nodes: Vec<Node<Context>>,
+    indexes: FxHashMap<String, usize>,
+}
+
+/// The routes registered under one key of the path tree.
+pub(crate) struct Node<Context> {
+    key: String,
+    /// Matches `key` alone, to try this node when the node the path tree
+    /// picked has no route for the request.
+    paths: PathTree<()>,
+    /// The other nodes whose keys can match a path this key matches, from
+    /// the most to the least specific.
+    fallbacks: Vec<usize>,
+    routes: Vec<Route<Context>>,
+}
+
+impl<Context> Tree<Context> {
+    fn new() -> Self {
+        Self {
+            paths: PathTree::new(),
+            nodes: Vec::new(),
+            indexes: FxHashMap::default(),
+        }
+    }
+
+    /// Checks `route` against the routes already registered in its node,
+    /// then against the routes of other nodes matching some of its paths.
+    fn conflict(&self, method: &Method, route: &Route<Context>) -> Option<RouteError> {
+        let method = method.clone();
+        let new = route.pattern.raw.clone();
+
+        if let Some(index) = self.indexes.get(&route.pattern.key) {
+            if let Some(r) = self.nodes[*index].routes.iter().find(|r| r.shadows(route)) {
+                let existing = r.pattern.raw.clone();
+                return Some(if existing == new {
+                    RouteError::Duplicate {
+                        method,
+                        existing,
+                        route: new,
+                    }
+                } else {
+                    RouteError::Ambiguous {
+                        method,
+                        existing,
+                        route: new,
+                    }
+                });
+            }
+        }
+
+        self.nodes
+            .iter()
+            .filter(|node| node.key != route.pattern.key)
+            .flat_map(|node| &node.routes)
+            .find(|r| r.pattern.overlaps(&route.pattern))
+            .map(|r| RouteError::Overlap {
+                method,
+                existing: r.pattern.raw.clone(),
+                route: new,
+            })
+    }
+
+    /// Returns the index of the node the route was appended to.
+    fn insert(&mut self, route: Route<Context>) -> usize {
+        let key = route.pattern.key.clone();
+        match self.indexes.get(&key) {
+            Some(&index) => {
+                self.nodes[index].routes.push(route);
+                index
+            }
+            None => {
+                let index = self.nodes.len();
+                let mut paths = PathTree::new();
+                paths.insert(&key, ());
+
+                let pattern = Pattern::parse(&key).ok();
+                let mut fallbacks = Vec::new();
+                for (i, node) in self.nodes.iter_mut().enumerate() {
+                    let overlaps = match (&pattern, Pattern::parse(&node.key)) {
+                        (Some(a), Ok(b)) => a.overlaps(&b),
+                        _ => true,
+                    };
+                    if overlaps {
+                        fallbacks.push(i);
+                        node.fallbacks.push(index);
+                    }
+                }
+
+                self.nodes.push(Node {
+                    key: key.clone(),
+                    paths,
+                    fallbacks,
+                    routes: vec![route],
+                });
+                self.indexes.insert(key.clone(), index);
+                self.paths.insert(&key, index);
+
+                let ranks: Vec<_> = self.nodes.iter().map(|node| rank(&node.key)).collect();
+                for node in &mut self.nodes {
+                    node.fallbacks.sort_by(|a, b| ranks[*b].cmp(&ranks[*a]));
+                }
+                index
+            }
+        }
+    }
+
+    /// Looks up `path`, which may be `original` lowercased. Parameter values
+    /// are taken from `original`.
+    ///
+    /// When no route of the node found by the path tree accepts the request,
+    /// because of a constraint or a guard, its fallbacks matching `path` are
+    /// tried from the most to the least specific. A path no key matches
+    /// costs a single lookup.
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::type_complexity))]
+    fn find<'a>(
+        &'a self,
+        path: &str,
+        original: &str,
+        req: Option<&Request>,
+    ) -> Option<(&'a VecMiddleware<Context>, Vec<(String, String)>)> {
+        let (index, values) = self.paths.find(path)?;
+        let node = &self.nodes[*index];
+        node.find(original, values, req).or_else(|| {
+            node.fallbacks
+                .iter()
+                .map(|i| &self.nodes[*i])
+                .find_map(|node| {
+                    let (_, values) = node.paths.find(path)?;
+                    node.find(original, values, req)
+                })
+        })
+    }
+}
+
+impl<Context> Node<Context> {
+    /// Tries the routes of the node with the parameters found by the path
+    /// tree, their values taken from `original`.
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::type_complexity))]
+    fn find<'a>(
+        &'a self,
+        original: &str,
+        found: Vec<(&str, &str)>,
+        req: Option<&Request>,
+    ) -> Option<(&'a VecMiddleware<Context>, Vec<(String, String)>)> {
+        let values: Vec<_> = found
+            .into_iter()
+            .map(|(k, _)| k)
+            .zip(values(&self.key, original))
+            .collect();
+        self.routes.iter().find_map(|route| {
+            route
+                .pattern
+                .matches(&values)
+                .filter(|_| route.check(req))
+                .map(|params| {
+                    let params = params
+                        .into_iter()
+                        .map(|(k, v)| (k.to_owned(), decode_value(v)))
+                        .collect();
+                    (&route.middleware, params)
+                })
+        })
+    }
+}
+
+/// The parameter values of `path` for `key`, which matches it ignoring
+/// ASCII case, taken segment by segment. A parameter runs to the end of its
+/// segment and a wildcard to the end of the path.
+fn values<'p>(key: &str, path: &'p str) -> Vec<&'p str> {
+    let mut values = Vec::new();
+    let mut offset = 0;
+    for (k, segment) in key.split('/').zip(path.split('/')) {
+        if let Some(i) = k.find('*') {
+            values.push(path.get(offset + i..).unwrap_or(""));
+            break;
+        }
+        if let Some(i) = k.find(':') {
+            values.push(&segment[i..]);
+        }
+        offset += segment.len() + 1;
+    }
+    values
+}
+
+/// Static segments rank above parameters, parameters above wildcards, the
+/// order the path tree prefers them in.
+fn rank(key: &str) -> Vec<u8> {
+    key.split('/')
+        .map(|segment| {
+            if segment.contains('*') {
+                0
+            } else if segment.contains(':') {
+                1
+            } else {
+                2
+            }
+        })
+        .collect()
+}
+
+impl<Context> Clone for Node<Context> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            paths: self.paths.clone(),
+            fallbacks: self.fallbacks.clone(),
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+impl<Context> Clone for Tree<Context> {
+    fn clone(&self) -> Self {
+        Self {
+            paths: self.paths.clone(),
+            nodes: self.nodes.clone(),
+            indexes: self.indexes.clone(),
+        }
+    }
+}
+
+/// The result of routing a request.
+pub enum Match<'a, Context> {
+    Found(&'a VecMiddleware<Context>, Vec<(String, String)>),
+    /// The canonical location of the requested path, with the query string.
+    Redirect(String),
+    /// The path has routes for these methods only.
+    MethodNotAllowed(Vec<Method>),
+    NotFound,
+}
 
 pub struct Router<Context> {
     path: String,
     trees: Trees<Context>,
+    last: Vec<(Method, usize)>,
+    trailing_slash: TrailingSlash,
+    case_insensitive: bool,
+    inflections: Inflections,
     pub middleware: VecMiddleware<Context>,
 }
 
@@ -36,6 +290,10 @@ impl<Context: Send + 'static> Router<Context> {
         Self {
             path: "/".to_owned(),
             trees: Trees::default(),
+            last: Vec::new(),
+            trailing_slash: TrailingSlash::default(),
+            case_insensitive: false,
+            inflections: Inflections::default(),
             middleware: Vec::new(),
         }
     }
@@ -50,17 +308,50 @@ impl<Context: Send + 'static> Router<Context> {
         self
     }
 
+    pub fn trailing_slash(&mut self, policy: TrailingSlash) -> &mut Self {
+        self.trailing_slash = policy;
+        self
+    }
+
+    /// Irregular and uncountable words used to name resource paths and id
+    /// params. Must be set before any resource is registered.
+    pub fn inflections(&mut self) -> &mut Inflections {
+        &mut self.inflections
+    }
+
+    /// Matches static segments ignoring ASCII case, parameter values keep
+    /// their case. Must be set before any route is registered.
+    pub fn case_insensitive(&mut self, yes: bool) -> &mut Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Logs an error and skips the scope if its path equals the parent
+    /// path, use `try_scope` to handle the error.
     pub fn scope<F>(&mut self, path: &str, f: F) -> &mut Self
     where
         F: FnOnce(&mut Self),
+    {
+        if let Err(e) = self.try_scope(path, |router| {
+            f(router);
+            Ok(())
+        }) {
+            error!("{}", e);
+        }
+        self
+    }
+
+    pub fn try_scope<F>(&mut self, path: &str, f: F) -> Result<&mut Self, RouteError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), RouteError>,
     {
         let path = Self::join_paths(&self.path, path);
 
         if path == self.path {
-            panic!(
-                "dont put parent path `{}` = scope path `{}`",
-                self.path, path
-            );
+            return Err(RouteError::InvalidScope {
+                parent: self.path.clone(),
+                scope: path,
+            });
         }
 
         let middleware = if self.path == "/" {
@@ -73,21 +364,42 @@ impl<Context: Send + 'static> Router<Context> {
             path,
             middleware,
             trees: self.trees.clone(),
+            last: Vec::new(),
+            trailing_slash: self.trailing_slash,
+            case_insensitive: self.case_insensitive,
+            inflections: self.inflections.clone(),
         };
 
-        f(&mut router);
+        f(&mut router)?;
 
         self.trees.clone_from(&router.trees);
+        self.last.clear();
 
-        self
+        Ok(self)
     }
 
+    /// Registers a route. A route whose pattern is invalid or which can never
+    /// be reached is logged as an error and skipped, overlaps with other
+    /// routes are only logged.
     fn _handle(
         &mut self,
         path: &str,
         method: Method,
         handler: BoxDynHandler<Context>,
     ) -> &mut Self {
+        if let Err(e) = self._try_handle(path, method, handler, false) {
+            error!("{}", e);
+        }
+        self
+    }
+
+    fn _try_handle(
+        &mut self,
+        path: &str,
+        method: Method,
+        handler: BoxDynHandler<Context>,
+        strict: bool,
+    ) -> Result<&mut Self, RouteError> {
         let path = &Self::join_paths(&self.path, path);
         let mut middleware = if self.path == "/" {
             vec![]
@@ -96,25 +408,109 @@ impl<Context: Send + 'static> Router<Context> {
         };
         middleware.push(Arc::new(box_dyn_handler_into_middleware(handler)));
 
+        let mut pattern = Pattern::parse(path).map_err(|reason| RouteError::InvalidPattern {
+            pattern: path.to_owned(),
+            reason,
+        })?;
+
+        if self.case_insensitive {
+            pattern.key.make_ascii_lowercase();
+        }
+
+        let route = Route {
+            pattern,
+            middleware,
+            guards: Vec::new(),
+        };
+
+        let tree = self.trees.entry(method.clone()).or_insert_with(Tree::new);
+
+        match tree.conflict(&method, &route) {
+            Some(e) if strict => return Err(e),
+            Some(e @ RouteError::Overlap { .. }) => debug!("{}", e),
+            None => {}
+            Some(e) => return Err(e),
+        }
+
         info!("route: {} {}", method, path);
 
-        self.trees
-            .entry(method)
-            .or_insert_with(PathTree::new)
-            .insert(path, middleware);
+        let index = tree.insert(route);
 
+        self.last.push((method, index));
+
+        Ok(self)
+    }
+
+    /// Adds a guard to the routes registered by the last call.
+    ///
+    /// ```ignore
+    /// router
+    ///     .post("/users", create_from_json)
+    ///     .guard(guard::content_type("application/json"))
+    ///     .post("/users", create_from_form);
+    /// ```
+    pub fn guard(&mut self, g: impl Guard) -> &mut Self {
+        let g: Arc<dyn Guard> = Arc::new(g);
+        for (method, index) in &self.last {
+            if let Some(route) = self
+                .trees
+                .get_mut(method)
+                .and_then(|tree| tree.nodes[*index].routes.last_mut())
+            {
+                route.guards.push(g.clone());
+            }
+        }
+        self
+    }
+
+    /// Adds a middleware in front of the handlers of the routes registered by
+    /// the last call.
+    ///
+    /// ```ignore
+    /// router
+    ///     .post("/uploads", upload)
+    ///     .route_middleware(BodyLimit::new(64 * 1024 * 1024));
+    /// ```
+    pub fn route_middleware(&mut self, m: impl Middleware<Context>) -> &mut Self {
+        let m: Arc<dyn Middleware<Context>> = Arc::new(m);
+        for (method, index) in &self.last {
+            if let Some(route) = self
+                .trees
+                .get_mut(method)
+                .and_then(|tree| tree.nodes[*index].routes.last_mut())
+            {
+                let handler = route.middleware.len() - 1;
+                route.middleware.insert(handler, m.clone());
+            }
+        }
         self
     }
 
+    /// Registers a route. Invalid and unreachable routes are logged as
+    /// errors and skipped, the first of two identical routes is kept. Use
+    /// `try_handle` to fail on them instead.
     pub fn handle(
         &mut self,
         path: &str,
         method: Method,
         h: impl Handler<Context> + Clone,
     ) -> &mut Self {
+        self.last.clear();
         self._handle(path, method, into_box_dyn_handler(h))
     }
 
+    /// Like `handle`, but returns an error instead of registering a route
+    /// that is invalid, can never be reached or overlaps an existing one.
+    pub fn try_handle(
+        &mut self,
+        path: &str,
+        method: Method,
+        h: impl Handler<Context> + Clone,
+    ) -> Result<&mut Self, RouteError> {
+        self.last.clear();
+        self._try_handle(path, method, into_box_dyn_handler(h), true)
+    }
+
     pub fn get(&mut self, path: &str, h: impl Handler<Context> + Clone) -> &mut Self {
         self.handle(path, Method::GET, h)
     }
@@ -152,15 +548,26 @@ impl<Context: Send + 'static> Router<Context> {
     }
 
     pub fn any(&mut self, path: &str, h: impl Handler<Context> + Clone) -> &mut Self {
-        self.handle(path, Method::GET, h.clone())
-            .handle(path, Method::POST, h.clone())
-            .handle(path, Method::DELETE, h.clone())
-            .handle(path, Method::PATCH, h.clone())
-            .handle(path, Method::PUT, h.clone())
-            .handle(path, Method::OPTIONS, h.clone())
-            .handle(path, Method::HEAD, h.clone())
-            .handle(path, Method::CONNECT, h.clone())
-            .handle(path, Method::TRACE, h)
+        let h = into_box_dyn_handler(h);
+        self.last.clear();
+        self._handle(path, Method::GET, h.clone())
+            ._handle(path, Method::POST, h.clone())
+            ._handle(path, Method::DELETE, h.clone())
+            ._handle(path, Method::PATCH, h.clone())
+            ._handle(path, Method::PUT, h.clone())
+            ._handle(path, Method::OPTIONS, h.clone())
+            ._handle(path, Method::HEAD, h.clone())
+            ._handle(path, Method::CONNECT, h.clone())
+            ._handle(path, Method::TRACE, h)
+    }
+
+    /// Registers the actions a `ResourceController` has a handler for.
+    pub fn resources_with(
+        &mut self,
+        path: &str,
+        controller: impl ResourceController<Context>,
+    ) -> &mut Self {
+        self.resources(path, &controller::into_maps(&controller))
     }
 
     pub fn resource(
@@ -173,10 +580,20 @@ impl<Context: Send + 'static> Router<Context> {
         } else {
             path
         };
-        let path = &to_singular(s);
+        let path = self.inflections.singularize(s);
+        self.resource_at(&path, maps)
+    }
+
+    /// Like `resource`, with the path used as given instead of singularized.
+    pub fn resource_at(
+        &mut self,
+        path: &str,
+        maps: &[(Resource, BoxDynHandler<Context>)],
+    ) -> &mut Self {
+        self.last.clear();
         for (resource, handler) in maps {
             let (sub_path, method) = resource.as_tuple();
-            let path = &Self::join_paths(&path, sub_path);
+            let path = &Self::join_paths(path, sub_path);
             self._handle(path, method, handler.clone());
         }
         self
@@ -193,25 +610,201 @@ impl<Context: Send + 'static> Router<Context> {
                 self.path.rsplitn(2, '/').collect::<Vec<&str>>()[0],
             )
         } else {
-            (to_plural(path), path)
+            (self.inflections.pluralize(path), path)
         };
-        let spath = to_singular(s);
+        let param = self.id_param(s);
+        self.resources_at(&p, &param, maps)
+    }
+
+    /// Like `resources`, with the collection path and the id param given
+    /// instead of inflected from the name.
+    ///
+    /// ```ignore
+    /// router.resources_at("artikel", "artikel_id", &maps);
+    /// ```
+    pub fn resources_at(
+        &mut self,
+        path: &str,
+        param: &str,
+        maps: &[(Resources, BoxDynHandler<Context>)],
+    ) -> &mut Self {
+        let param = param.trim_start_matches(':');
+        self.last.clear();
         for (resources, handler) in maps {
             let (sub_path, method) = resources.as_tuple();
-            let path = &Self::join_paths(&p, &sub_path.replace("id", &(spath.to_owned() + "_id")));
+            let path = &Self::join_paths(path, &with_param(sub_path, param));
             self._handle(path, method, handler.clone());
         }
         self
     }
 
+    /// Registers nested resources with member and collection routes.
+    /// See `ResourcesBuilder`.
+    pub fn build_resources<F>(&mut self, path: &str, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut ResourcesBuilder<Context>),
+    {
+        let mut builder = ResourcesBuilder::new(path);
+        f(&mut builder);
+        self.last.clear();
+        self.register_resources(&builder, None, false);
+        self
+    }
+
+    fn register_resources(
+        &mut self,
+        builder: &ResourcesBuilder<Context>,
+        parent: Option<&str>,
+        shallow: bool,
+    ) {
+        let shallow = shallow || builder.shallow;
+        let param = builder
+            .param
+            .clone()
+            .unwrap_or_else(|| self.id_param(&builder.name));
+        let path = builder
+            .path
+            .clone()
+            .unwrap_or_else(|| self.inflections.pluralize(&builder.name));
+        let collection = Self::join_paths(parent.unwrap_or(""), &path);
+        let member = if shallow && parent.is_some() {
+            Self::join_paths("", &path)
+        } else {
+            collection.clone()
+        };
+        let member = Self::join_paths(&member, &format!(":{}", param));
+
+        for (action, handler) in &builder.actions {
+            if !builder.is_enabled(action) {
+                continue;
+            }
+            let (sub_path, method) = action.as_tuple();
+            let base = match action {
+                Resources::Index | Resources::Create | Resources::New => &collection,
+                _ => &member,
+            };
+            let path = Self::join_paths(base, sub_path.trim_start_matches(":id"));
+            self._handle(&path, method, handler.clone());
+        }
+
+        for (method, sub_path, handler) in &builder.collections {
+            let path = Self::join_paths(&collection, sub_path);
+            self._handle(&path, method.clone(), handler.clone());
+        }
+
+        for (method, sub_path, handler) in &builder.members {
+            let path = Self::join_paths(&member, sub_path);
+            self._handle(&path, method.clone(), handler.clone());
+        }
+
+        // A shallow child nests its own children under its shallow member
+        // path.
+        for nested in &builder.nested {
+            self.register_resources(nested, Some(&member), shallow);
+        }
+    }
+
+    /// `/posts` -> `post_id`
+    fn id_param(&self, name: &str) -> String {
+        let name = name.trim_matches('/').rsplit('/').next().unwrap_or("");
+        self.inflections.singularize(name) + "_id"
+    }
+
+    /// Finds the route for a decoded and normalized `path` and the method of
+    /// `req`, checking the guards of the candidates against `req`.
     #[cfg_attr(feature = "cargo-clippy", allow(clippy::type_complexity))]
     pub fn find<'a>(
         &'a self,
-        path: &'a str,
-        method: Method,
-    ) -> Option<(&'a VecMiddleware<Context>, Vec<(&'a str, &'a str)>)> {
-        let tree = self.trees.get(&method)?;
-        tree.find(path)
+        path: &str,
+        req: &Request,
+    ) -> Option<(&'a VecMiddleware<Context>, Vec<(String, String)>)> {
+        self.find_in(self.trees.get(req.method())?, path, Some(req))
+    }
+
+    /// Finds a route by method only, the way `find` did before guards.
+    /// Guarded routes never match since there is no request to check.
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::type_complexity))]
+    pub fn find_method<'a>(
+        &'a self,
+        path: &str,
+        method: &Method,
+    ) -> Option<(&'a VecMiddleware<Context>, Vec<(String, String)>)> {
+        self.find_in(self.trees.get(method)?, path, None)
+    }
+
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::type_complexity))]
+    fn find_in<'a>(
+        &self,
+        tree: &'a Tree<Context>,
+        path: &str,
+        req: Option<&Request>,
+    ) -> Option<(&'a VecMiddleware<Context>, Vec<(String, String)>)> {
+        if self.case_insensitive {
+            tree.find(&path.to_ascii_lowercase(), path, req)
+        } else {
+            tree.find(path, path, req)
+        }
+    }
+
+    /// The methods with a route for `path`, other than the request's. A
+    /// path no key matches costs one path tree lookup per method.
+    pub fn allowed_methods(&self, path: &str, req: &Request) -> Vec<Method> {
+        let mut methods: Vec<Method> = self
+            .trees
+            .iter()
+            .filter(|(method, tree)| {
+                *method != req.method() && self.find_in(tree, path, Some(req)).is_some()
+            })
+            .map(|(method, _)| method.clone())
+            .collect();
+        methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        methods
+    }
+
+    /// Finds the route for the request after decoding and normalizing its
+    /// path, applying the trailing slash policy.
+    pub fn route<'a>(&'a self, req: &Request) -> Match<'a, Context> {
+        let path = decode(req.uri().path());
+        let normalized = normalize(&path);
+
+        let redirect = |location: &str| {
+            let location = encode(location);
+            Match::Redirect(match req.uri().query() {
+                Some(query) => format!("{}?{}", location, query),
+                None => location,
+            })
+        };
+
+        if let Some((m, p)) = self.find(&normalized, req) {
+            if self.trailing_slash == TrailingSlash::Redirect && normalized != path {
+                return redirect(&normalized);
+            }
+            return Match::Found(m, p);
+        }
+
+        let not_found = || {
+            let methods = self.allowed_methods(&normalized, req);
+            if methods.is_empty() {
+                Match::NotFound
+            } else {
+                Match::MethodNotAllowed(methods)
+            }
+        };
+
+        if self.trailing_slash == TrailingSlash::Strict {
+            return not_found();
+        }
+
+        let toggled = match toggle_trailing_slash(&normalized) {
+            Some(toggled) => toggled,
+            None => return not_found(),
+        };
+
+        match self.find(&toggled, req) {
+            Some(_) if self.trailing_slash == TrailingSlash::Redirect => redirect(&toggled),
+            Some((m, p)) => Match::Found(m, p),
+            None => not_found(),
+        }
     }
 
     pub(crate) fn join_paths(a: &str, b: &str) -> String {
@@ -226,6 +819,8 @@ impl<Context> fmt::Debug for Router<Context> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Router")
             .field("path", &self.path)
+            .field("trailing_slash", &self.trailing_slash)
+            .field("case_insensitive", &self.case_insensitive)
             .finish()
     }
 }
@@ -0,0 +1,136 @@
+//! Path normalization.
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use std::borrow::Cow;
+
+/// Characters that are escaped again when a decoded path is sent back in a
+/// `Location` header. `%` is kept as is since `%2F`, `%5C` and `%25` are never
+/// decoded.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// How the router treats a trailing slash on the request path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailingSlash {
+    /// `/users` and `/users/` are different routes.
+    Strict,
+    /// Redirects to the registered form of the path. Paths with duplicate
+    /// slashes or `.`/`..` segments are redirected to their canonical form too.
+    Redirect,
+    /// `/users` and `/users/` both match whichever one is registered.
+    MatchBoth,
+}
+
+impl Default for TrailingSlash {
+    fn default() -> Self {
+        Self::Strict
+    }
+}
+
+/// Collapses duplicate slashes and resolves `.` and `..` segments.
+/// `..` never climbs above the root.
+pub fn normalize(path: &str) -> Cow<'_, str> {
+    let is_normal = path.starts_with('/')
+        && !path.contains("//")
+        && path
+            .split('/')
+            .all(|segment| segment != "." && segment != "..");
+
+    if is_normal {
+        return Cow::Borrowed(path);
+    }
+
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            _ => segments.push(segment),
+        }
+    }
+
+    let mut normalized = String::with_capacity(path.len());
+    normalized.push('/');
+    normalized.push_str(&segments.join("/"));
+    if !segments.is_empty() && (path.ends_with('/') || path.ends_with("/.")) {
+        normalized.push('/');
+    }
+
+    Cow::Owned(normalized)
+}
+
+/// `/users` <-> `/users/`, the root path has no alternative.
+pub fn toggle_trailing_slash(path: &str) -> Option<String> {
+    if path == "/" {
+        None
+    } else if path.ends_with('/') {
+        Some(path.trim_end_matches('/').to_owned())
+    } else {
+        Some(path.to_owned() + "/")
+    }
+}
+
+/// Percent-decodes the path before matching, except `%2F`, `%5C` and `%25`.
+///
+/// Keeping `%2F` and `%5C` encoded means an encoded slash or backslash never
+/// splits a segment or changes which route matches. Keeping `%25` encoded
+/// keeps the remaining escapes unambiguous. Parameter values are decoded
+/// once more after matching, see `decode_value`, so handlers get `a%2Fb` as
+/// `a/b` and must not trust a value to be a single path segment. Paths
+/// which do not decode to UTF-8 are matched as is.
+pub fn decode(path: &str) -> Cow<'_, str> {
+    if !path.contains('%') {
+        return Cow::Borrowed(path);
+    }
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if let (Some(h), Some(l)) = (
+                bytes.get(i + 1).and_then(|b| (*b as char).to_digit(16)),
+                bytes.get(i + 2).and_then(|b| (*b as char).to_digit(16)),
+            ) {
+                let b = (h * 16 + l) as u8;
+                if b != b'/' && b != b'\\' && b != b'%' {
+                    decoded.push(b);
+                    i += 3;
+                    continue;
+                }
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(_) => Cow::Borrowed(path),
+    }
+}
+
+/// Decodes the escapes `decode` kept in a parameter value, values which do
+/// not decode to UTF-8 are kept as is.
+pub(crate) fn decode_value(value: &str) -> String {
+    match percent_decode_str(value).decode_utf8() {
+        Ok(v) => v.into_owned(),
+        Err(_) => value.to_owned(),
+    }
+}
+
+/// Escapes a decoded path for use in a `Location` header.
+pub(crate) fn encode(path: &str) -> String {
+    utf8_percent_encode(path, PATH).to_string()
+}
@@ -1,9 +1,14 @@
 //! Resource enums.
 
 use http::Method;
+use std::{
+    fmt,
+    mem::{discriminant, Discriminant},
+};
+use trek_core::{into_box_dyn_handler, BoxDynHandler, Handler};
 
 /// Resource
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Resource {
     Show,
     Create,
@@ -27,7 +32,7 @@ impl Resource {
 }
 
 /// Resources
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Resources {
     Index,
     Create,
@@ -51,3 +56,157 @@ impl Resources {
         }
     }
 }
+
+/// Replaces the `:id` segment of a `Resources` sub path.
+pub(crate) fn with_param(sub_path: &str, param: &str) -> String {
+    sub_path
+        .split('/')
+        .map(|s| {
+            if s == ":id" {
+                format!(":{}", param)
+            } else {
+                s.to_owned()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("/")
+}
+
+/// Builder for `Router::build_resources`.
+///
+/// ```ignore
+/// router.build_resources("posts", |posts| {
+///     posts
+///         .actions(&[(Resources::Index, index), (Resources::Show, show)])
+///         .member(Method::POST, "publish", publish)
+///         .collection(Method::GET, "search", search)
+///         .resources("comments", |comments| {
+///             comments.shallow(true).actions(&[(Resources::Show, show_comment)]);
+///         });
+/// });
+/// ```
+///
+/// Registers `GET /posts`, `GET /posts/:post_id`, `POST /posts/:post_id/publish`,
+/// `GET /posts/search` and `GET /comments/:comment_id`.
+pub struct ResourcesBuilder<Context> {
+    pub(crate) name: String,
+    pub(crate) path: Option<String>,
+    pub(crate) param: Option<String>,
+    pub(crate) only: Option<Vec<Discriminant<Resources>>>,
+    pub(crate) except: Vec<Discriminant<Resources>>,
+    pub(crate) shallow: bool,
+    pub(crate) actions: Vec<(Resources, BoxDynHandler<Context>)>,
+    pub(crate) members: Vec<(Method, String, BoxDynHandler<Context>)>,
+    pub(crate) collections: Vec<(Method, String, BoxDynHandler<Context>)>,
+    pub(crate) nested: Vec<ResourcesBuilder<Context>>,
+}
+
+impl<Context: Send + 'static> ResourcesBuilder<Context> {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.trim_matches('/').to_owned(),
+            path: None,
+            param: None,
+            only: None,
+            except: Vec::new(),
+            shallow: false,
+            actions: Vec::new(),
+            members: Vec::new(),
+            collections: Vec::new(),
+            nested: Vec::new(),
+        }
+    }
+
+    /// The path segment of the collection, the plural of the name by default.
+    pub fn path(&mut self, path: &str) -> &mut Self {
+        self.path = Some(path.trim_matches('/').to_owned());
+        self
+    }
+
+    /// The name of the id param, `{singular}_id` by default.
+    pub fn param(&mut self, name: &str) -> &mut Self {
+        self.param = Some(name.trim_start_matches(':').to_owned());
+        self
+    }
+
+    /// Only registers these actions.
+    pub fn only(&mut self, actions: &[Resources]) -> &mut Self {
+        self.only = Some(actions.iter().map(discriminant).collect());
+        self
+    }
+
+    /// Registers all actions except these.
+    pub fn except(&mut self, actions: &[Resources]) -> &mut Self {
+        self.except = actions.iter().map(discriminant).collect();
+        self
+    }
+
+    /// Mounts member routes without the parent path when nested,
+    /// `/comments/:comment_id` instead of `/posts/:post_id/comments/:comment_id`.
+    /// Applies to this resource and all resources nested below.
+    pub fn shallow(&mut self, yes: bool) -> &mut Self {
+        self.shallow = yes;
+        self
+    }
+
+    pub fn action(&mut self, action: Resources, h: impl Handler<Context> + Clone) -> &mut Self {
+        self.actions.push((action, into_box_dyn_handler(h)));
+        self
+    }
+
+    pub fn actions(&mut self, maps: &[(Resources, BoxDynHandler<Context>)]) -> &mut Self {
+        self.actions.extend(maps.iter().cloned());
+        self
+    }
+
+    /// A route on a single resource, e.g. `POST /posts/:post_id/publish`.
+    pub fn member(
+        &mut self,
+        method: Method,
+        path: &str,
+        h: impl Handler<Context> + Clone,
+    ) -> &mut Self {
+        self.members
+            .push((method, path.to_owned(), into_box_dyn_handler(h)));
+        self
+    }
+
+    /// A route on the collection, e.g. `GET /posts/search`.
+    pub fn collection(
+        &mut self,
+        method: Method,
+        path: &str,
+        h: impl Handler<Context> + Clone,
+    ) -> &mut Self {
+        self.collections
+            .push((method, path.to_owned(), into_box_dyn_handler(h)));
+        self
+    }
+
+    /// Nests resources below a single resource of this one.
+    pub fn resources<F>(&mut self, name: &str, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut builder = Self::new(name);
+        f(&mut builder);
+        self.nested.push(builder);
+        self
+    }
+
+    pub(crate) fn is_enabled(&self, action: &Resources) -> bool {
+        let d = discriminant(action);
+        self.only.as_ref().map_or(true, |only| only.contains(&d)) && !self.except.contains(&d)
+    }
+}
+
+impl<Context> fmt::Debug for ResourcesBuilder<Context> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ResourcesBuilder")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("param", &self.param)
+            .field("shallow", &self.shallow)
+            .finish()
+    }
+}
@@ -0,0 +1,347 @@
+//! Route patterns with typed parameter constraints.
+//!
+//! A parameter can be followed by a constraint in angle brackets:
+//!
+//! ```text
+//! /users/:id<u64>
+//! /posts/:slug<[a-z-]+>
+//! /files/:uuid<uuid>
+//! ```
+//!
+//! Builtin constraints are the integer and float primitives, `bool`, `uuid`,
+//! `alpha` and `alnum`. Anything else is compiled as a regular expression
+//! which must match the whole segment.
+
+use regex::Regex;
+use std::{fmt, sync::Arc};
+
+use trek_core::{Middleware, Request};
+
+use crate::Guard;
+
+pub(crate) type VecMiddleware<Context> = Vec<Arc<dyn Middleware<Context>>>;
+
+/// A constraint on the value of a path parameter.
+#[derive(Clone)]
+pub enum Constraint {
+    U8,
+    U16,
+    U32,
+    U64,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    Isize,
+    F32,
+    F64,
+    Bool,
+    Uuid,
+    Alpha,
+    Alnum,
+    Regex(String, Regex),
+}
+
+impl Constraint {
+    pub fn parse(s: &str) -> Result<Self, regex::Error> {
+        Ok(match s {
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "usize" => Self::Usize,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "isize" => Self::Isize,
+            "f32" => Self::F32,
+            "f64" => Self::F64,
+            "bool" => Self::Bool,
+            "uuid" => Self::Uuid,
+            "alpha" => Self::Alpha,
+            "alnum" => Self::Alnum,
+            _ => Self::Regex(s.to_owned(), Regex::new(&format!("^(?:{})$", s))?),
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::Usize => "usize",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::Isize => "isize",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+            Self::Bool => "bool",
+            Self::Uuid => "uuid",
+            Self::Alpha => "alpha",
+            Self::Alnum => "alnum",
+            Self::Regex(s, _) => s,
+        }
+    }
+
+    pub fn matches(&self, v: &str) -> bool {
+        match self {
+            Self::U8 => v.parse::<u8>().is_ok(),
+            Self::U16 => v.parse::<u16>().is_ok(),
+            Self::U32 => v.parse::<u32>().is_ok(),
+            Self::U64 => v.parse::<u64>().is_ok(),
+            Self::Usize => v.parse::<usize>().is_ok(),
+            Self::I8 => v.parse::<i8>().is_ok(),
+            Self::I16 => v.parse::<i16>().is_ok(),
+            Self::I32 => v.parse::<i32>().is_ok(),
+            Self::I64 => v.parse::<i64>().is_ok(),
+            Self::Isize => v.parse::<isize>().is_ok(),
+            Self::F32 => v.parse::<f32>().is_ok(),
+            Self::F64 => v.parse::<f64>().is_ok(),
+            Self::Bool => v.parse::<bool>().is_ok(),
+            Self::Uuid => is_uuid(v),
+            Self::Alpha => !v.is_empty() && v.chars().all(char::is_alphabetic),
+            Self::Alnum => !v.is_empty() && v.chars().all(char::is_alphanumeric),
+            Self::Regex(_, re) => re.is_match(v),
+        }
+    }
+}
+
+impl PartialEq for Constraint {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Debug for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}>", self.as_str())
+    }
+}
+
+/// `8-4-4-4-12` hex digits, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+fn is_uuid(v: &str) -> bool {
+    let v = v.as_bytes();
+    v.len() == 36
+        && v.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        })
+}
+
+/// A parsed route pattern.
+///
+/// `key` is the pattern handed to the path tree: constraints are stripped,
+/// named parameters are renamed by position and wildcards lose their names,
+/// so `/users/:id<u64>` and `/users/:name` share one node and are tried in
+/// registration order.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    pub raw: String,
+    pub key: String,
+    pub params: Vec<(String, Option<Constraint>)>,
+}
+
+impl Pattern {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut key = String::with_capacity(raw.len());
+        let mut params = Vec::new();
+        let mut chars = raw.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != ':' && c != '*' {
+                if c == '<' || c == '>' {
+                    return Err(format!("unexpected `{}` outside of a parameter", c));
+                }
+                key.push(c);
+                continue;
+            }
+
+            let mut name = String::new();
+            while let Some(&n) = chars.peek() {
+                if n.is_ascii_alphanumeric() || n == '_' {
+                    name.push(n);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            let mut constraint = None;
+            if chars.peek() == Some(&'<') {
+                chars.next();
+                let mut depth = 1;
+                let mut s = String::new();
+                for n in chars.by_ref() {
+                    match n {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        break;
+                    }
+                    s.push(n);
+                }
+                if depth != 0 {
+                    return Err(format!("unclosed constraint on `{}{}`", c, name));
+                }
+                if s.is_empty() {
+                    return Err(format!("empty constraint on `{}{}`", c, name));
+                }
+                constraint = Some(Constraint::parse(&s).map_err(|e| e.to_string())?);
+            }
+
+            if c == ':' {
+                if name.is_empty() {
+                    return Err("missing parameter name after `:`".to_owned());
+                }
+                // The path tree captures a parameter up to the next `/`.
+                if chars.peek().map_or(false, |n| *n != '/') {
+                    return Err(format!("parameter `:{}` must end its segment", name));
+                }
+                key.push(':');
+                key.push('p');
+                key.push_str(&params.len().to_string());
+            } else {
+                key.push('*');
+            }
+
+            params.push((name, constraint));
+        }
+
+        Ok(Self {
+            raw: raw.to_owned(),
+            key,
+            params,
+        })
+    }
+
+    /// Checks the constraints against the values found by the path tree and
+    /// renames the parameters back to the names given in the pattern.
+    pub fn matches<'a>(&'a self, values: &[(&'a str, &'a str)]) -> Option<Vec<(&'a str, &'a str)>> {
+        if values.len() != self.params.len() {
+            return None;
+        }
+        self.params
+            .iter()
+            .zip(values)
+            .map(|((name, constraint), (k, v))| match constraint {
+                Some(c) if !c.matches(v) => None,
+                _ => Some((if name.is_empty() { *k } else { name.as_str() }, *v)),
+            })
+            .collect()
+    }
+
+    /// Whether every path matched by `other` is already matched by `self`,
+    /// given both patterns share a node.
+    pub fn shadows(&self, other: &Self) -> bool {
+        self.params
+            .iter()
+            .zip(&other.params)
+            .all(|((_, a), (_, b))| a.is_none() || a == b)
+    }
+
+    /// Whether some path is matched by both patterns although their keys
+    /// differ, e.g. `/users/:id` and `/users/new` or `/users/*`.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let mut a = self.segments().into_iter();
+        let mut b = other.segments().into_iter();
+        loop {
+            match (a.next(), b.next()) {
+                (None, None) => return true,
+                (None, _) | (_, None) => return false,
+                (Some(Segment::Wildcard), _) | (_, Some(Segment::Wildcard)) => return true,
+                (Some(Segment::Static(x)), Some(Segment::Static(y))) => {
+                    if x != y {
+                        return false;
+                    }
+                }
+                (Some(Segment::Static(s)), Some(Segment::Param(prefix, c)))
+                | (Some(Segment::Param(prefix, c)), Some(Segment::Static(s))) => {
+                    if s.len() <= prefix.len()
+                        || !s.starts_with(prefix)
+                        || c.map_or(false, |c| !c.matches(s))
+                    {
+                        return false;
+                    }
+                }
+                (Some(Segment::Param(x, _)), Some(Segment::Param(y, _))) => {
+                    if !x.starts_with(y) && !y.starts_with(x) {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+
+    fn segments(&self) -> Vec<Segment<'_>> {
+        let mut params = self.params.iter();
+        self.key
+            .split('/')
+            .map(|segment| match segment.find(|c| c == ':' || c == '*') {
+                None => Segment::Static(segment),
+                Some(_) if segment.contains('*') => Segment::Wildcard,
+                Some(i) => {
+                    // Only a parameter taking the whole segment can be
+                    // checked against a static segment.
+                    let n = segment.matches(':').count();
+                    let constraint = params.nth(n - 1).and_then(|(_, c)| c.as_ref());
+                    let whole = i == 0 && n == 1;
+                    Segment::Param(&segment[..i], constraint.filter(|_| whole))
+                }
+            })
+            .collect()
+    }
+}
+
+/// A segment of a path tree key.
+enum Segment<'a> {
+    Static(&'a str),
+    /// The static prefix and, for a whole segment, the constraint.
+    Param(&'a str, Option<&'a Constraint>),
+    Wildcard,
+}
+
+pub struct Route<Context> {
+    pub pattern: Pattern,
+    pub middleware: VecMiddleware<Context>,
+    pub guards: Vec<Arc<dyn Guard>>,
+}
+
+impl<Context> Route<Context> {
+    /// Runs the guards in order. Without a request only unguarded routes
+    /// pass.
+    pub fn check(&self, req: Option<&Request>) -> bool {
+        match req {
+            Some(req) => self.guards.iter().all(|g| g.check(req)),
+            None => self.guards.is_empty(),
+        }
+    }
+
+    pub fn shadows(&self, other: &Self) -> bool {
+        self.guards.is_empty() && self.pattern.shadows(&other.pattern)
+    }
+}
+
+impl<Context> Clone for Route<Context> {
+    fn clone(&self) -> Self {
+        Self {
+            pattern: self.pattern.clone(),
+            middleware: self.middleware.clone(),
+            guards: self.guards.clone(),
+        }
+    }
+}
+
+impl<Context> fmt::Debug for Route<Context> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Route")
+            .field("pattern", &self.pattern.raw)
+            .finish()
+    }
+}
@@ -0,0 +1,171 @@
+use futures::{executor::block_on, StreamExt};
+use http::Method;
+use trek_router::{RouteError, Router};
+
+/// The body of the response of the `GET` route matching `path`.
+fn body(router: &Router<()>, path: &str) -> String {
+    let (middleware, _) = router.find_method(path, &Method::GET).unwrap();
+    let res = block_on(middleware.last().unwrap().call(()));
+    let chunks = block_on(res.into_body().collect::<Vec<_>>());
+    chunks
+        .into_iter()
+        .map(|chunk| String::from_utf8(chunk.unwrap().to_vec()).unwrap())
+        .collect()
+}
+
+/// Registers `existing` then `route` with `try_handle`.
+fn conflict(existing: &str, route: &str) -> Option<RouteError> {
+    let mut router = Router::<()>::new();
+    router
+        .try_handle(existing, Method::GET, |_: ()| async { "" })
+        .unwrap();
+    router
+        .try_handle(route, Method::GET, |_: ()| async { "" })
+        .err()
+}
+
+fn assert_names(e: &RouteError, existing: &str, route: &str) {
+    let message = e.to_string();
+    assert!(message.contains(existing), "{}", message);
+    assert!(message.contains(route), "{}", message);
+}
+
+#[test]
+fn duplicate() {
+    let e = conflict("/users/:id", "/users/:id").unwrap();
+    assert_eq!(
+        e,
+        RouteError::Duplicate {
+            method: Method::GET,
+            existing: "/users/:id".to_owned(),
+            route: "/users/:id".to_owned(),
+        }
+    );
+    assert_names(&e, "GET", "/users/:id");
+}
+
+#[test]
+fn ambiguous() {
+    let e = conflict("/users/:name", "/users/:id<u64>").unwrap();
+    assert_eq!(
+        e,
+        RouteError::Ambiguous {
+            method: Method::GET,
+            existing: "/users/:name".to_owned(),
+            route: "/users/:id<u64>".to_owned(),
+        }
+    );
+    assert_names(&e, "/users/:name", "/users/:id<u64>");
+
+    assert_eq!(conflict("/users/:id<u64>", "/users/:name"), None);
+}
+
+#[test]
+fn param_and_wildcard_overlap() {
+    let e = conflict("/users/:id", "/users/*").unwrap();
+    assert_eq!(
+        e,
+        RouteError::Overlap {
+            method: Method::GET,
+            existing: "/users/:id".to_owned(),
+            route: "/users/*".to_owned(),
+        }
+    );
+    assert_names(&e, "/users/:id", "/users/*");
+
+    let e = conflict("/files/*", "/files/:dir/:name").unwrap();
+    assert_names(&e, "/files/*", "/files/:dir/:name");
+}
+
+#[test]
+fn param_and_static_overlap() {
+    let e = conflict("/a/:x", "/a/b").unwrap();
+    assert_eq!(
+        e,
+        RouteError::Overlap {
+            method: Method::GET,
+            existing: "/a/:x".to_owned(),
+            route: "/a/b".to_owned(),
+        }
+    );
+    assert_names(&e, "/a/:x", "/a/b");
+
+    let e = conflict("/a/b/c", "/a/:x/c").unwrap();
+    assert_names(&e, "/a/b/c", "/a/:x/c");
+}
+
+#[test]
+fn disjoint_routes() {
+    assert_eq!(conflict("/a/:x<u64>", "/a/b"), None);
+    assert_eq!(conflict("/a/:x", "/a/b/c"), None);
+    assert_eq!(conflict("/a/:x", "/b/:x"), None);
+    assert_eq!(conflict("/a/", "/a/:x"), None);
+    assert_eq!(conflict("/a/:x/b", "/a/:y/c"), None);
+}
+
+#[test]
+fn other_methods_do_not_conflict() {
+    let mut router = Router::<()>::new();
+    router
+        .try_handle("/users/:id", Method::GET, |_: ()| async { "" })
+        .unwrap()
+        .try_handle("/users/:id", Method::PUT, |_: ()| async { "" })
+        .unwrap();
+}
+
+#[test]
+fn invalid_pattern() {
+    let e = conflict("/a", "/users/:id<u64").unwrap();
+    match &e {
+        RouteError::InvalidPattern { pattern, .. } => assert_eq!(pattern, "/users/:id<u64"),
+        _ => panic!("expected an invalid pattern, got {:?}", e),
+    }
+    assert_names(&e, "/users/:id<u64", "unclosed");
+}
+
+#[test]
+fn invalid_scope() {
+    let mut router = Router::<()>::new();
+    let e = router
+        .try_scope("/api", |api| api.try_scope("", |_| Ok(())).map(|_| ()))
+        .unwrap_err();
+    assert_eq!(
+        e,
+        RouteError::InvalidScope {
+            parent: "/api".to_owned(),
+            scope: "/api".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn handle_skips_unreachable_routes() {
+    let mut router = Router::<()>::new();
+    router
+        .get("/users/:id", |_: ()| async { "first" })
+        .get("/users/:id", |_: ()| async { "second" })
+        .get("/users/:id<u64", |_: ()| async { "" });
+    assert_eq!(body(&router, "/users/1"), "first");
+}
+
+#[test]
+fn scope_skips_an_invalid_scope() {
+    let mut router = Router::<()>::new();
+    router.scope("/api", |api| {
+        api.scope("", |same| {
+            same.get("/users", |_: ()| async { "" });
+        });
+        api.get("/posts", |_: ()| async { "" });
+    });
+    assert!(router.find_method("/api/users", &Method::GET).is_none());
+    assert!(router.find_method("/api/posts", &Method::GET).is_some());
+}
+
+#[test]
+fn handle_allows_overlaps() {
+    let mut router = Router::<()>::new();
+    router
+        .get("/users/new", |_: ()| async { "" })
+        .get("/users/:id", |_: ()| async { "" })
+        .get("/users/*", |_: ()| async { "" });
+}
@@ -0,0 +1,130 @@
+use http::Method;
+use trek_core::{Body, Request};
+use trek_router::{Constraint, Match, Pattern, Router};
+
+fn request(path: &str) -> Request {
+    http::Request::builder()
+        .method(Method::GET)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn params(router: &Router<()>, path: &str) -> Option<Vec<(String, String)>> {
+    match router.route(&request(path)) {
+        Match::Found(_, params) => Some(params),
+        _ => None,
+    }
+}
+
+#[test]
+fn builtin_constraints() {
+    assert_eq!(Constraint::parse("u64").unwrap(), Constraint::U64);
+    assert!(Constraint::U64.matches("42"));
+    assert!(!Constraint::U64.matches("-1"));
+    assert!(!Constraint::U64.matches("abc"));
+    assert!(!Constraint::U64.matches(""));
+    assert!(Constraint::I8.matches("-128"));
+    assert!(!Constraint::I8.matches("128"));
+    assert!(Constraint::Bool.matches("true"));
+    assert!(Constraint::Alpha.matches("café"));
+    assert!(!Constraint::Alnum.matches("a-1"));
+}
+
+#[test]
+fn uuid_constraint() {
+    let uuid = Constraint::parse("uuid").unwrap();
+    assert_eq!(uuid, Constraint::Uuid);
+    assert!(uuid.matches("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    assert!(!uuid.matches("67e55044-10b1-426f-9247-bb680e5fe0c"));
+    assert!(!uuid.matches("67e55044_10b1_426f_9247_bb680e5fe0c8"));
+    assert!(!uuid.matches("g7e55044-10b1-426f-9247-bb680e5fe0c8"));
+}
+
+#[test]
+fn regex_constraint() {
+    let slug = Constraint::parse("[a-z-]+").unwrap();
+    assert_eq!(slug.as_str(), "[a-z-]+");
+    assert!(slug.matches("hello-world"));
+    assert!(!slug.matches("Hello"));
+    assert!(!slug.matches("a1"), "the whole segment must match");
+    assert!(Constraint::parse("[a-z").is_err());
+}
+
+#[test]
+fn parse_pattern() {
+    let pattern = Pattern::parse("/users/:id<u64>/posts/:slug<[a-z]+>/*").unwrap();
+    assert_eq!(pattern.key, "/users/:p0/posts/:p1/*");
+    assert_eq!(
+        pattern.params,
+        vec![
+            ("id".to_owned(), Some(Constraint::U64)),
+            (
+                "slug".to_owned(),
+                Some(Constraint::parse("[a-z]+").unwrap())
+            ),
+            ("".to_owned(), None),
+        ]
+    );
+
+    let nested = Pattern::parse("/x/:code<[0-9]{3}<?>?>").unwrap();
+    assert_eq!(
+        nested.params[0].1.as_ref().unwrap().as_str(),
+        "[0-9]{3}<?>?"
+    );
+}
+
+#[test]
+fn malformed_patterns() {
+    assert!(Pattern::parse("/users/:id<u64").is_err());
+    assert!(Pattern::parse("/users/:id<>").is_err());
+    assert!(Pattern::parse("/users/<u64>").is_err());
+    assert!(Pattern::parse("/users/:id>").is_err());
+    assert!(Pattern::parse("/users/:<u64>").is_err());
+    assert!(Pattern::parse("/users/:id<[a-z>").is_err());
+    assert!(Pattern::parse("/:a-:b").is_err());
+    assert!(Pattern::parse("/files/:name.json").is_err());
+    assert!(Pattern::parse("/v:version/users").is_ok());
+}
+
+#[test]
+fn matches_values() {
+    let pattern = Pattern::parse("/users/:id<u64>").unwrap();
+    assert_eq!(pattern.matches(&[("p0", "7")]), Some(vec![("id", "7")]));
+    assert_eq!(pattern.matches(&[("p0", "seven")]), None);
+    assert_eq!(pattern.matches(&[]), None);
+}
+
+#[test]
+fn constraint_falls_through() {
+    let mut router = Router::new();
+    router
+        .get("/users/:id<u64>", |_: ()| async { "" })
+        .get("/users/:name<alpha>", |_: ()| async { "" });
+
+    assert_eq!(
+        params(&router, "/users/42"),
+        Some(vec![("id".to_owned(), "42".to_owned())])
+    );
+    assert_eq!(
+        params(&router, "/users/abc"),
+        Some(vec![("name".to_owned(), "abc".to_owned())])
+    );
+    assert_eq!(params(&router, "/users/a1"), None);
+}
+
+#[test]
+fn constraint_falls_through_to_wildcard() {
+    let mut router = Router::new();
+    router
+        .get("/users/:id<u64>", |_: ()| async { "" })
+        .get("/users/*", |_: ()| async { "" });
+
+    assert_eq!(
+        params(&router, "/users/42"),
+        Some(vec![("id".to_owned(), "42".to_owned())])
+    );
+    let wildcard = params(&router, "/users/abc").unwrap();
+    assert_eq!(wildcard.len(), 1);
+    assert_eq!(wildcard[0].1, "abc");
+}
@@ -0,0 +1,70 @@
+use http::Method;
+use trek_core::{into_box_dyn_handler, Body, BoxDynHandler, Request};
+use trek_router::{Match, ResourceController, Router};
+
+struct Users;
+
+impl ResourceController<()> for Users {
+    fn index(&self) -> Option<BoxDynHandler<()>> {
+        Some(into_box_dyn_handler(|_: ()| async { "index" }))
+    }
+
+    fn show(&self) -> Option<BoxDynHandler<()>> {
+        Some(into_box_dyn_handler(|_: ()| async { "show" }))
+    }
+
+    fn update(&self) -> Option<BoxDynHandler<()>> {
+        Some(into_box_dyn_handler(|_: ()| async { "update" }))
+    }
+}
+
+fn route(router: &Router<()>, method: Method, path: &str) -> Result<(), Vec<Method>> {
+    let req: Request = http::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap();
+    match router.route(&req) {
+        Match::Found(..) => Ok(()),
+        Match::MethodNotAllowed(allow) => Err(allow),
+        _ => Err(vec![]),
+    }
+}
+
+#[test]
+fn routes_implemented_actions() {
+    let mut router = Router::new();
+    router.resources_with("users", Users);
+
+    assert_eq!(route(&router, Method::GET, "/users"), Ok(()));
+    assert_eq!(route(&router, Method::GET, "/users/1"), Ok(()));
+    assert_eq!(route(&router, Method::PUT, "/users/1"), Ok(()));
+    assert_eq!(route(&router, Method::PATCH, "/users/1"), Ok(()));
+}
+
+#[test]
+fn other_actions_answer_405_with_allow() {
+    let mut router = Router::new();
+    router.resources_with("users", Users);
+
+    assert_eq!(
+        route(&router, Method::POST, "/users"),
+        Err(vec![Method::GET])
+    );
+    assert_eq!(
+        route(&router, Method::DELETE, "/users/1"),
+        Err(vec![Method::GET, Method::PATCH, Method::PUT])
+    );
+    assert_eq!(route(&router, Method::GET, "/users/1/edit"), Err(vec![]));
+}
+
+#[test]
+fn empty_controller_routes_nothing() {
+    struct Empty;
+    impl ResourceController<()> for Empty {}
+
+    let mut router = Router::new();
+    router.resources_with("users", Empty);
+
+    assert_eq!(route(&router, Method::GET, "/users"), Err(vec![]));
+}
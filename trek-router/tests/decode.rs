@@ -0,0 +1,120 @@
+use http::Method;
+use trek_core::{Body, Request};
+use trek_router::{decode, Match, Router};
+
+fn request(path: &str) -> Request {
+    http::Request::builder()
+        .method(Method::GET)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn params(router: &Router<()>, path: &str) -> Option<Vec<(String, String)>> {
+    match router.route(&request(path)) {
+        Match::Found(_, params) => Some(params),
+        _ => None,
+    }
+}
+
+fn value(router: &Router<()>, path: &str) -> Option<String> {
+    params(router, path).map(|mut params| params.remove(0).1)
+}
+
+#[test]
+fn decode_keeps_separators_encoded() {
+    assert_eq!(decode("/caf%C3%A9"), "/café");
+    assert_eq!(decode("/a%20b"), "/a b");
+    assert_eq!(decode("/a%2Fb%2fc"), "/a%2Fb%2fc");
+    assert_eq!(decode("/a%5Cb"), "/a%5Cb");
+    assert_eq!(decode("/100%25"), "/100%25");
+    assert_eq!(decode("/%FF"), "/%FF");
+}
+
+#[test]
+fn decoded_static_segments() {
+    let mut router = Router::new();
+    router.get("/café", |_: ()| async { "" });
+    assert!(params(&router, "/caf%C3%A9").is_some());
+}
+
+#[test]
+fn decoded_params() {
+    let mut router = Router::new();
+    router.get("/users/:name", |_: ()| async { "" });
+    assert_eq!(
+        value(&router, "/users/j%C3%B6rg%20k")
+            .as_ref()
+            .map(String::as_str),
+        Some("jörg k")
+    );
+    assert_eq!(
+        value(&router, "/users/a%2Fb").as_ref().map(String::as_str),
+        Some("a/b")
+    );
+    assert_eq!(
+        value(&router, "/users/a%5Cb").as_ref().map(String::as_str),
+        Some("a\\b")
+    );
+    assert_eq!(
+        value(&router, "/users/100%25").as_ref().map(String::as_str),
+        Some("100%")
+    );
+    assert_eq!(
+        value(&router, "/users/%252F").as_ref().map(String::as_str),
+        Some("%2F")
+    );
+}
+
+#[test]
+fn encoded_slashes_do_not_change_the_route() {
+    let mut router = Router::new();
+    router
+        .get("/p/:name", |_: ()| async { "" })
+        .get("/p/:dir/:name", |_: ()| async { "" });
+
+    let params = params(&router, "/p/a%2Fb").unwrap();
+    assert_eq!(params, vec![("name".to_owned(), "a/b".to_owned())]);
+}
+
+#[test]
+fn encoded_traversal_is_decoded_once() {
+    let mut router = Router::new();
+    router.get("/static/*", |_: ()| async { "" });
+
+    // Handlers serving files must check the components of the value, as
+    // trek-serve does.
+    assert_eq!(
+        value(&router, "/static/..%2F..%2Fetc%2Fpasswd")
+            .as_ref()
+            .map(String::as_str),
+        Some("../../etc/passwd")
+    );
+    assert_eq!(
+        value(&router, "/static/..%252F..%252Fetc")
+            .as_ref()
+            .map(String::as_str),
+        Some("..%2F..%2Fetc")
+    );
+
+    assert_eq!(
+        value(&router, "/static/css/%2E%2E/app.css")
+            .as_ref()
+            .map(String::as_str),
+        Some("app.css")
+    );
+    assert_eq!(params(&router, "/static/%2E%2E/%2E%2E/etc/passwd"), None);
+}
+
+#[test]
+fn case_insensitive_keeps_value_case() {
+    let mut router = Router::new();
+    router
+        .case_insensitive(true)
+        .get("/Users/:name/files/*", |_: ()| async { "" });
+
+    let params = params(&router, "/USERS/JohnDoe/FILES/Docs/A.txt").unwrap();
+    let values: Vec<_> = params.iter().map(|(_, v)| v.as_str()).collect();
+    assert_eq!(params[0].0, "name");
+    assert_eq!(values, vec!["JohnDoe", "Docs/A.txt"]);
+}
@@ -0,0 +1,147 @@
+use http::{header::CONTENT_TYPE, Method};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use trek_core::{Body, Request};
+use trek_router::{guard, Match, Router};
+
+fn request(method: Method, uri: &str, content_type: Option<&str>) -> Request {
+    let mut builder = http::Request::builder().method(method).uri(uri);
+    if let Some(content_type) = content_type {
+        builder = builder.header(CONTENT_TYPE, content_type);
+    }
+    builder.body(Body::empty()).unwrap()
+}
+
+/// The name of the matched parameter tells which route was picked.
+fn matched(router: &Router<()>, req: &Request) -> Option<String> {
+    match router.route(req) {
+        Match::Found(_, params) => Some(params[0].0.clone()),
+        _ => None,
+    }
+}
+
+#[test]
+fn guards_are_tried_in_registration_order() {
+    let mut router = Router::new();
+    router
+        .post("/users/:json", |_: ()| async { "" })
+        .guard(guard::content_type("application/json"))
+        .post("/users/:form", |_: ()| async { "" })
+        .guard(guard::content_type("application/x-www-form-urlencoded"))
+        .post("/users/:other", |_: ()| async { "" });
+
+    let json = request(
+        Method::POST,
+        "/users/1",
+        Some("application/json; charset=utf-8"),
+    );
+    let form = request(
+        Method::POST,
+        "/users/1",
+        Some("application/x-www-form-urlencoded"),
+    );
+    let text = request(Method::POST, "/users/1", Some("text/plain"));
+
+    assert_eq!(
+        matched(&router, &json).as_ref().map(String::as_str),
+        Some("json")
+    );
+    assert_eq!(
+        matched(&router, &form).as_ref().map(String::as_str),
+        Some("form")
+    );
+    assert_eq!(
+        matched(&router, &text).as_ref().map(String::as_str),
+        Some("other")
+    );
+}
+
+#[test]
+fn guards_of_a_route_short_circuit() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let mut router = Router::new();
+    router
+        .get("/:id", |_: ()| async { "" })
+        .guard(|_: &Request| false)
+        .guard(move |_: &Request| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            true
+        });
+
+    let req = request(Method::GET, "/1", None);
+    assert_eq!(matched(&router, &req), None);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn failed_guards_fall_through_to_not_found() {
+    let mut router = Router::new();
+    router
+        .post("/upload", |_: ()| async { "" })
+        .guard(guard::content_type("multipart/form-data"))
+        .get("/upload", |_: ()| async { "" });
+
+    let req = request(Method::POST, "/upload", Some("text/plain"));
+    match router.route(&req) {
+        Match::MethodNotAllowed(methods) => assert_eq!(methods, vec![Method::GET]),
+        _ => panic!("expected 405"),
+    }
+
+    let req = request(Method::PUT, "/other", None);
+    match router.route(&req) {
+        Match::NotFound => {}
+        _ => panic!("expected 404"),
+    }
+}
+
+#[test]
+fn header_guard_accepts_any_case() {
+    let mut router = Router::new();
+    router
+        .post("/:json", |_: ()| async { "" })
+        .guard(guard::header("Content-Type", "Application/JSON"));
+
+    let json = request(Method::POST, "/1", Some("application/json"));
+    let text = request(Method::POST, "/1", Some("text/plain"));
+    assert_eq!(
+        matched(&router, &json).as_ref().map(String::as_str),
+        Some("json")
+    );
+    assert_eq!(matched(&router, &text), None);
+}
+
+#[test]
+fn query_guard_decodes_values() {
+    let mut router = Router::new();
+    router
+        .get("/:search", |_: ()| async { "" })
+        .guard(guard::query("q", "a b/c"));
+
+    for uri in &["/s?q=a%20b%2Fc", "/s?x=1&q=a+b/c"] {
+        let req = request(Method::GET, uri, None);
+        assert_eq!(
+            matched(&router, &req).as_ref().map(String::as_str),
+            Some("search"),
+            "{}",
+            uri
+        );
+    }
+    let req = request(Method::GET, "/s?q=a", None);
+    assert_eq!(matched(&router, &req), None);
+}
+
+#[test]
+fn find_method_skips_guarded_routes() {
+    let mut router = Router::new();
+    router
+        .get("/open/:id", |_: ()| async { "" })
+        .get("/guarded/:id", |_: ()| async { "" })
+        .guard(|_: &Request| true);
+
+    assert!(router.find_method("/open/1", &Method::GET).is_some());
+    assert!(router.find_method("/guarded/1", &Method::GET).is_none());
+    assert!(router.find_method("/open/1", &Method::POST).is_none());
+}
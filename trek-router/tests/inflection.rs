@@ -0,0 +1,122 @@
+use http::Method;
+use trek_core::{into_box_dyn_handler, Body, Request};
+use trek_router::{Inflections, Match, Resource, Resources, Router};
+
+fn request(path: &str) -> Request {
+    http::Request::builder()
+        .method(Method::GET)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn params(router: &Router<()>, path: &str) -> Option<Vec<(String, String)>> {
+    match router.route(&request(path)) {
+        Match::Found(_, params) => Some(params),
+        _ => None,
+    }
+}
+
+#[test]
+fn uncountable() {
+    let inflections = Inflections::new();
+    assert_eq!(inflections.singularize("news"), "news");
+    assert_eq!(inflections.pluralize("news"), "news");
+    assert_eq!(inflections.singularize("data"), "data");
+    assert_eq!(inflections.pluralize("data"), "data");
+}
+
+#[test]
+fn irregular() {
+    let inflections = Inflections::new();
+    assert_eq!(inflections.singularize("statuses"), "status");
+    assert_eq!(inflections.singularize("status"), "status");
+    assert_eq!(inflections.pluralize("status"), "statuses");
+    assert_eq!(inflections.pluralize("statuses"), "statuses");
+    assert_eq!(inflections.pluralize("person"), "people");
+    assert_eq!(inflections.singularize("people"), "person");
+}
+
+#[test]
+fn custom() {
+    let mut inflections = Inflections::empty();
+    inflections
+        .irregular("curriculum", "curricula")
+        .uncountable("feedback");
+    assert_eq!(inflections.pluralize("curriculum"), "curricula");
+    assert_eq!(inflections.singularize("curricula"), "curriculum");
+    assert_eq!(inflections.pluralize("feedback"), "feedback");
+}
+
+#[test]
+fn last_segment() {
+    let inflections = Inflections::new();
+    assert_eq!(inflections.pluralize("/admin/status"), "/admin/statuses");
+    assert_eq!(inflections.singularize("/admin/news"), "/admin/news");
+}
+
+#[test]
+fn resources_paths() {
+    let h = into_box_dyn_handler(|_: ()| async { "" });
+    let mut router = Router::new();
+    router
+        .resources("news", &[(Resources::Show, h.clone())])
+        .resources("status", &[(Resources::Show, h.clone())]);
+
+    assert_eq!(
+        params(&router, "/news/1"),
+        Some(vec![("news_id".to_owned(), "1".to_owned())])
+    );
+    assert_eq!(
+        params(&router, "/statuses/1"),
+        Some(vec![("status_id".to_owned(), "1".to_owned())])
+    );
+}
+
+#[test]
+fn overrides() {
+    let h = into_box_dyn_handler(|_: ()| async { "" });
+    let mut router = Router::new();
+    router.build_resources("artikel", |r| {
+        r.path("artikel")
+            .param("id")
+            .actions(&[(Resources::Show, h.clone())]);
+    });
+
+    assert_eq!(
+        params(&router, "/artikel/1"),
+        Some(vec![("id".to_owned(), "1".to_owned())])
+    );
+}
+
+#[test]
+fn keeps_case() {
+    let inflections = Inflections::new();
+    assert_eq!(inflections.pluralize("Person"), "People");
+    assert_eq!(inflections.singularize("People"), "Person");
+    assert_eq!(inflections.pluralize("STATUS"), "STATUSES");
+    assert_eq!(inflections.singularize("News"), "News");
+    assert_eq!(inflections.pluralize("/admin/Child"), "/admin/Children");
+}
+
+#[test]
+fn uncountable_once() {
+    let mut inflections = Inflections::empty();
+    inflections.uncountable("news").uncountable("News");
+    assert_eq!(format!("{:?}", inflections).matches("\"news\"").count(), 1);
+}
+
+#[test]
+fn entry_point_overrides() {
+    let h = into_box_dyn_handler(|_: ()| async { "" });
+    let mut router = Router::new();
+    router
+        .resources_at("artikel", ":artikel_id", &[(Resources::Show, h.clone())])
+        .resource_at("status", &[(Resource::Show, h.clone())]);
+
+    assert_eq!(
+        params(&router, "/artikel/1"),
+        Some(vec![("artikel_id".to_owned(), "1".to_owned())])
+    );
+    assert_eq!(params(&router, "/status"), Some(vec![]));
+}
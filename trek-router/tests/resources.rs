@@ -0,0 +1,191 @@
+use http::Method;
+use trek_core::{into_box_dyn_handler, Body, BoxDynHandler, Request};
+use trek_router::{Match, Resources, Router};
+
+fn handler() -> BoxDynHandler<()> {
+    into_box_dyn_handler(|_: ()| async { "" })
+}
+
+fn all() -> Vec<(Resources, BoxDynHandler<()>)> {
+    vec![
+        (Resources::Index, handler()),
+        (Resources::Create, handler()),
+        (Resources::New, handler()),
+        (Resources::Show, handler()),
+        (Resources::Update(Method::PUT), handler()),
+        (Resources::Update(Method::PATCH), handler()),
+        (Resources::Delete, handler()),
+        (Resources::Edit, handler()),
+    ]
+}
+
+/// The params of the route matching `method` and `path`.
+fn params(router: &Router<()>, method: Method, path: &str) -> Option<Vec<(String, String)>> {
+    let req: Request = http::Request::builder()
+        .method(method)
+        .uri(path)
+        .body(Body::empty())
+        .unwrap();
+    match router.route(&req) {
+        Match::Found(_, params) => Some(params),
+        _ => None,
+    }
+}
+
+fn names(router: &Router<()>, method: Method, path: &str) -> Option<Vec<String>> {
+    params(router, method, path).map(|params| params.into_iter().map(|(k, _)| k).collect())
+}
+
+#[test]
+fn resources_at_replaces_only_the_id_segment() {
+    let mut router = Router::new();
+    router.resources_at("videos", "guid", &all());
+
+    assert_eq!(
+        params(&router, Method::GET, "/videos/7/edit"),
+        Some(vec![("guid".to_owned(), "7".to_owned())])
+    );
+    assert_eq!(names(&router, Method::GET, "/videos/new"), Some(vec![]));
+    assert_eq!(
+        names(&router, Method::PATCH, "/videos/7").unwrap(),
+        ["guid"]
+    );
+    assert_eq!(names(&router, Method::GET, "/videos/7/guid"), None);
+}
+
+#[test]
+fn nested_resources() {
+    let mut router = Router::new();
+    router.build_resources("posts", |posts| {
+        posts.actions(&all()).resources("comments", |comments| {
+            comments.actions(&all());
+        });
+    });
+
+    assert_eq!(
+        names(&router, Method::GET, "/posts/1/comments").unwrap(),
+        ["post_id"]
+    );
+    assert_eq!(
+        params(&router, Method::GET, "/posts/1/comments/2/edit"),
+        Some(vec![
+            ("post_id".to_owned(), "1".to_owned()),
+            ("comment_id".to_owned(), "2".to_owned()),
+        ])
+    );
+    assert_eq!(names(&router, Method::GET, "/comments/2"), None);
+}
+
+#[test]
+fn shallow_resources() {
+    let mut router = Router::new();
+    router.build_resources("posts", |posts| {
+        posts.actions(&all()).resources("comments", |comments| {
+            comments
+                .shallow(true)
+                .actions(&all())
+                .resources("likes", |likes| {
+                    likes.actions(&all());
+                });
+        });
+    });
+
+    // Collection routes keep the parent, member routes drop it.
+    assert_eq!(
+        names(&router, Method::GET, "/posts/1/comments").unwrap(),
+        ["post_id"]
+    );
+    assert_eq!(
+        names(&router, Method::POST, "/posts/1/comments").unwrap(),
+        ["post_id"]
+    );
+    assert_eq!(
+        names(&router, Method::GET, "/comments/2").unwrap(),
+        ["comment_id"]
+    );
+    assert_eq!(names(&router, Method::GET, "/posts/1/comments/2"), None);
+
+    // The shallow setting applies to the resources nested below.
+    assert_eq!(
+        names(&router, Method::GET, "/comments/2/likes").unwrap(),
+        ["comment_id"]
+    );
+    assert_eq!(
+        names(&router, Method::GET, "/likes/3").unwrap(),
+        ["like_id"]
+    );
+    assert_eq!(
+        names(&router, Method::GET, "/posts/1/comments/2/likes"),
+        None
+    );
+}
+
+#[test]
+fn only_and_except() {
+    let mut router = Router::new();
+    router
+        .build_resources("posts", |posts| {
+            posts
+                .only(&[Resources::Index, Resources::Show])
+                .actions(&all());
+        })
+        .build_resources("tags", |tags| {
+            tags.except(&[Resources::Delete, Resources::Edit])
+                .actions(&all());
+        });
+
+    assert!(names(&router, Method::GET, "/posts").is_some());
+    assert!(names(&router, Method::GET, "/posts/1").is_some());
+    assert!(names(&router, Method::POST, "/posts").is_none());
+    assert!(
+        names(&router, Method::GET, "/posts/new").is_some(),
+        "`new` is a post id"
+    );
+    assert!(names(&router, Method::GET, "/posts/1/edit").is_none());
+
+    assert!(names(&router, Method::POST, "/tags").is_some());
+    assert!(names(&router, Method::PUT, "/tags/1").is_some());
+    assert!(names(&router, Method::DELETE, "/tags/1").is_none());
+    assert!(names(&router, Method::GET, "/tags/1/edit").is_none());
+}
+
+#[test]
+fn member_and_collection_routes() {
+    let mut router = Router::new();
+    router.build_resources("posts", |posts| {
+        posts
+            .action(Resources::Show, |_: ()| async { "" })
+            .member(Method::POST, "publish", |_: ()| async { "" })
+            .collection(Method::GET, "search", |_: ()| async { "" });
+    });
+
+    assert_eq!(
+        names(&router, Method::POST, "/posts/1/publish").unwrap(),
+        ["post_id"]
+    );
+    assert_eq!(
+        names(&router, Method::GET, "/posts/search").unwrap(),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn path_and_param_overrides() {
+    let mut router = Router::new();
+    router.build_resources("people", |people| {
+        people
+            .path("personen")
+            .param(":pid")
+            .actions(&all())
+            .resources("notes", |notes| {
+                notes.param("nid").actions(&all());
+            });
+    });
+
+    assert_eq!(names(&router, Method::GET, "/personen/1").unwrap(), ["pid"]);
+    assert_eq!(
+        names(&router, Method::GET, "/personen/1/notes/2/edit").unwrap(),
+        ["pid", "nid"]
+    );
+    assert_eq!(names(&router, Method::GET, "/people/1"), None);
+}
@@ -0,0 +1,86 @@
+use http::Method;
+use trek_core::{Body, Request};
+use trek_router::{normalize, Match, Router, TrailingSlash};
+
+fn request(uri: &str) -> Request {
+    http::Request::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn router(policy: TrailingSlash) -> Router<()> {
+    let mut router = Router::new();
+    router
+        .trailing_slash(policy)
+        .get("/users", |_: ()| async { "" })
+        .get("/posts/", |_: ()| async { "" });
+    router
+}
+
+/// `found`, `404` or the redirect location.
+fn outcome(router: &Router<()>, uri: &str) -> String {
+    match router.route(&request(uri)) {
+        Match::Found(..) => "found".to_owned(),
+        Match::Redirect(location) => location,
+        Match::MethodNotAllowed(_) => "405".to_owned(),
+        Match::NotFound => "404".to_owned(),
+    }
+}
+
+#[test]
+fn normalize_paths() {
+    assert_eq!(normalize("/users/1"), "/users/1");
+    assert_eq!(normalize("//users///1"), "/users/1");
+    assert_eq!(normalize("/users/./1"), "/users/1");
+    assert_eq!(normalize("/users/1/.."), "/users");
+    assert_eq!(normalize("/users/1/../"), "/users/");
+    assert_eq!(normalize("/users/."), "/users/");
+    assert_eq!(normalize("/../../etc"), "/etc");
+    assert_eq!(normalize("/.."), "/");
+    assert_eq!(normalize("users"), "/users");
+}
+
+#[test]
+fn strict() {
+    let router = router(TrailingSlash::Strict);
+    assert_eq!(outcome(&router, "/users"), "found");
+    assert_eq!(outcome(&router, "/users/"), "404");
+    assert_eq!(outcome(&router, "/posts/"), "found");
+    assert_eq!(outcome(&router, "/posts"), "404");
+    assert_eq!(outcome(&router, "//users"), "found");
+}
+
+#[test]
+fn redirect() {
+    let router = router(TrailingSlash::Redirect);
+    assert_eq!(outcome(&router, "/users"), "found");
+    assert_eq!(outcome(&router, "/users/"), "/users");
+    assert_eq!(outcome(&router, "/posts"), "/posts/");
+    assert_eq!(outcome(&router, "//users"), "/users");
+    assert_eq!(outcome(&router, "/posts/./"), "/posts/");
+    assert_eq!(outcome(&router, "/other/../users/"), "/users");
+    assert_eq!(outcome(&router, "/other"), "404");
+}
+
+#[test]
+fn redirect_keeps_the_query() {
+    let router = router(TrailingSlash::Redirect);
+    assert_eq!(
+        outcome(&router, "/users/?page=2&q=a%20b"),
+        "/users?page=2&q=a%20b"
+    );
+    assert_eq!(outcome(&router, "//posts?x"), "/posts/?x");
+}
+
+#[test]
+fn match_both() {
+    let router = router(TrailingSlash::MatchBoth);
+    assert_eq!(outcome(&router, "/users"), "found");
+    assert_eq!(outcome(&router, "/users/"), "found");
+    assert_eq!(outcome(&router, "/posts"), "found");
+    assert_eq!(outcome(&router, "/posts/"), "found");
+    assert_eq!(outcome(&router, "/users//"), "found");
+    assert_eq!(outcome(&router, "/other/"), "404");
+}
@@ -3,11 +3,11 @@ use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use hyper::Response as HyperResponse;
 use std::{
     io,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 use tokio::fs::File;
-use trek_core::{Body, Context, Handler, IntoResponse, Response, Result};
+use trek_core::{Body, Context, Handler, IntoResponse, Response, Result, StatusCode};
 
 mod template;
 
@@ -66,6 +66,18 @@ impl ServeHandler {
             }
         };
 
+        // Param values are percent-decoded after matching, an encoded
+        // `..%2F` arrives here as `../` although the router normalized the
+        // path.
+        let is_traversal = Path::new(&suffix_path).components().any(|c| match c {
+            Component::Normal(_) => false,
+            _ => true,
+        });
+
+        if is_traversal {
+            return Ok(StatusCode::NOT_FOUND.into_response());
+        }
+
         path.push(suffix_path.clone());
 
         let file = File::open(path.clone()).await?;
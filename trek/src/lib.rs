@@ -17,13 +17,28 @@ pub mod middleware;
 
 #[doc(inline)]
 pub use trek_core::{
-    box_dyn_handler_into_middleware, helpers, html, into_box_dyn_handler, json, Body,
-    BoxDynHandler, Context, Error, ErrorResponse, Handler, IntoResponse, Middleware, Parameters,
-    Request, Response, Result, StatusCode,
+    box_dyn_handler_into_middleware, broadcast, debug, extract, helpers, html,
+    into_box_dyn_handler, json, long_poll, problem_handler, sse, stream, Body, BoxDynHandler,
+    Broadcast, Context, Error, ErrorResponse, Handler, IntoResponse, Middleware, Negotiate,
+    Parameters, Problem, Rejection, Request, Response, Result, StatusCode, DEFAULT_BODY_LIMIT,
 };
 
+#[cfg(feature = "cbor")]
 #[doc(inline)]
-pub use trek_router::{Resource, Resources, Router};
+pub use trek_core::cbor;
+#[cfg(feature = "msgpack")]
+#[doc(inline)]
+pub use trek_core::msgpack;
+
+#[cfg(feature = "websocket")]
+#[doc(inline)]
+pub use trek_core::websocket;
+
+#[doc(inline)]
+pub use trek_router::{
+    guard, Inflections, Resource, ResourceController, Resources, ResourcesBuilder, RouteError,
+    Router, TrailingSlash,
+};
 
 #[doc(inline)]
-pub use crate::trek::Trek;
+pub use crate::trek::{ErrorHandler, Trek};
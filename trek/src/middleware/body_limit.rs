@@ -0,0 +1,21 @@
+use crate::{Context, Middleware, Response};
+use futures::future::BoxFuture;
+
+/// Sets the limit of the request body for the next middleware and handlers.
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimit {
+    limit: usize,
+}
+
+impl BodyLimit {
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<Context<State>> for BodyLimit {
+    fn call<'a>(&'a self, mut cx: Context<State>) -> BoxFuture<'a, Response> {
+        cx.set_body_limit(self.limit);
+        cx.next()
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{Context, Error, ErrorResponse, IntoResponse, Middleware, Response};
+use futures::future::{BoxFuture, FutureExt};
+use http::Method;
+use std::{any::Any, error, fmt, panic::AssertUnwindSafe, sync::Arc};
+
+/// A panic caught by `CatchPanic`, rendered as `500 Internal Server Error`.
+#[derive(Debug, Clone)]
+pub struct Panic {
+    pub method: Method,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "handler panicked on {} {}: {}",
+            self.method, self.path, self.message
+        )
+    }
+}
+
+impl error::Error for Panic {}
+
+impl ErrorResponse for Panic {}
+
+type OnPanic = dyn Fn(&Panic) + Send + Sync;
+
+/// Turns panics of the next middleware and handlers into `500` responses
+/// instead of dropping the connection.
+///
+/// The panic is logged and answered with a `Panic` error, so it goes through
+/// the error handler of the app like any other error.
+///
+/// ```ignore
+/// app.router().middleware(CatchPanic::new().on_panic(|_| PANICS.fetch_add(1, Relaxed)));
+/// ```
+#[derive(Clone, Default)]
+pub struct CatchPanic {
+    on_panic: Option<Arc<OnPanic>>,
+}
+
+impl CatchPanic {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` for each caught panic, for example to record it in metrics.
+    pub fn on_panic(mut self, f: impl Fn(&Panic) + Send + Sync + 'static) -> Self {
+        self.on_panic = Some(Arc::new(f));
+        self
+    }
+}
+
+impl fmt::Debug for CatchPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CatchPanic").finish()
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<Context<State>> for CatchPanic {
+    fn call<'a>(&'a self, cx: Context<State>) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let method = cx.method().clone();
+            let path = cx.uri().path().to_owned();
+
+            match AssertUnwindSafe(cx.next()).catch_unwind().await {
+                Ok(res) => res,
+                Err(payload) => {
+                    let panic = Panic {
+                        method,
+                        path,
+                        message: panic_message(payload.as_ref()),
+                    };
+                    log::error!("{}", panic);
+                    if let Some(f) = &self.on_panic {
+                        f(&panic);
+                    }
+                    Error::from(panic).into_response()
+                }
+            }
+        })
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_owned()
+    }
+}
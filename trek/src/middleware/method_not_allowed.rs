@@ -0,0 +1,33 @@
+use crate::{Body, Context, Middleware, Response};
+use futures::future::BoxFuture;
+use http::{header::ALLOW, status::StatusCode, HeaderValue, Method};
+
+#[derive(Debug, Clone)]
+pub struct MethodNotAllowed {
+    allow: Vec<Method>,
+}
+
+impl MethodNotAllowed {
+    pub fn new(allow: Vec<Method>) -> Self {
+        Self { allow }
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<Context<State>> for MethodNotAllowed {
+    fn call<'a>(&'a self, _: Context<State>) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let mut res = Response::new(Body::empty());
+            *res.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+            let allow = self
+                .allow
+                .iter()
+                .map(Method::as_str)
+                .collect::<Vec<&str>>()
+                .join(", ");
+            if let Ok(allow) = HeaderValue::from_str(&allow) {
+                res.headers_mut().insert(ALLOW, allow);
+            }
+            res
+        })
+    }
+}
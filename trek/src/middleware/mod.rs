@@ -3,8 +3,16 @@ mod cookies;
 #[cfg(feature = "cookies")]
 pub use cookies::{Cookie, CookieJar, Cookies, CookiesContextExt, CookiesMiddleware};
 
+mod body_limit;
+mod catch_panic;
 mod logger;
+mod method_not_allowed;
 mod not_found;
+mod redirect;
 
+pub use body_limit::BodyLimit;
+pub use catch_panic::{CatchPanic, Panic};
 pub use logger::Logger;
+pub use method_not_allowed::MethodNotAllowed;
 pub use not_found::NotFound;
+pub use redirect::Redirect;
@@ -0,0 +1,37 @@
+use crate::{Body, Context, Middleware, Response};
+use futures::future::BoxFuture;
+use http::{header::LOCATION, status::StatusCode, HeaderValue, Method};
+
+/// Redirects to the canonical path, `301` for `GET` and `HEAD` and `308`
+/// otherwise so the method and body are kept.
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    location: String,
+}
+
+impl Redirect {
+    pub fn new(location: impl Into<String>) -> Self {
+        Self {
+            location: location.into(),
+        }
+    }
+}
+
+impl<State: Send + Sync + 'static> Middleware<Context<State>> for Redirect {
+    fn call<'a>(&'a self, cx: Context<State>) -> BoxFuture<'a, Response> {
+        Box::pin(async move {
+            let mut res = Response::new(Body::empty());
+            *res.status_mut() = match *cx.method() {
+                Method::GET | Method::HEAD => StatusCode::MOVED_PERMANENTLY,
+                _ => StatusCode::PERMANENT_REDIRECT,
+            };
+            match HeaderValue::from_str(&self.location) {
+                Ok(location) => {
+                    res.headers_mut().insert(LOCATION, location);
+                }
+                Err(_) => *res.status_mut() = StatusCode::BAD_REQUEST,
+            }
+            res
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use http::request::Parts;
 use hyper::{
     server::Server,
     service::{make_service_fn, service_fn},
@@ -5,11 +6,23 @@ use hyper::{
 };
 use std::{convert::Infallible, fmt, sync::Arc};
 
-use crate::{middleware::NotFound, Context, Router};
+use trek_router::Match;
+
+use crate::{
+    middleware::{MethodNotAllowed, NotFound, Redirect},
+    Context, Request, Response, Router, DEFAULT_BODY_LIMIT,
+};
+
+/// Renders the errors returned by handlers and middleware, see
+/// `Trek::error_handler`.
+pub type ErrorHandler = dyn Fn(crate::Error, &Parts) -> Response + Send + Sync;
 
 pub struct Trek<State> {
     state: State,
     router: Router<Context<State>>,
+    body_limit: usize,
+    strict_content_type: bool,
+    error_handler: Option<Arc<ErrorHandler>>,
 }
 
 impl<State: Send + Sync + 'static> Trek<State> {
@@ -17,6 +30,9 @@ impl<State: Send + Sync + 'static> Trek<State> {
         Self {
             state,
             router: Router::new(),
+            body_limit: DEFAULT_BODY_LIMIT,
+            strict_content_type: true,
+            error_handler: None,
         }
     }
 
@@ -24,6 +40,46 @@ impl<State: Send + Sync + 'static> Trek<State> {
         &mut self.router
     }
 
+    /// Sets the default limit of request bodies, see `Context::body_limit`.
+    pub fn body_limit(&mut self, limit: usize) -> &mut Self {
+        self.body_limit = limit;
+        self
+    }
+
+    /// Whether `json` and `form` bodies must have a matching `content-type`,
+    /// see `Context::set_strict_content_type`.
+    pub fn strict_content_type(&mut self, strict: bool) -> &mut Self {
+        self.strict_content_type = strict;
+        self
+    }
+
+    /// Sets the one place where errors are rendered.
+    ///
+    /// Errors are logged first, server errors at error level and client
+    /// errors at debug level. The handler then gets the error and the parts
+    /// of the request as the handlers left them, with the route params as a
+    /// `RouteParams` extension, see `Context::parts_on_drop`. When the
+    /// context outlives the response, it only gets the method, uri and
+    /// version. Without a handler, errors are rendered by their
+    /// `ErrorResponse`.
+    ///
+    /// ```ignore
+    /// app.error_handler(|err, parts| {
+    ///     let mut res = err.as_error_response().render_response();
+    ///     if let Some(id) = parts.headers.get("x-request-id") {
+    ///         res.headers_mut().insert("x-request-id", id.clone());
+    ///     }
+    ///     res
+    /// });
+    /// ```
+    pub fn error_handler<F>(&mut self, f: F) -> &mut Self
+    where
+        F: Fn(crate::Error, &Parts) -> Response + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Arc::new(f));
+        self
+    }
+
     #[cfg(feature = "tokio")]
     pub async fn run(self, addr: impl std::net::ToSocketAddrs) -> std::io::Result<()> {
         let addr = addr
@@ -42,35 +98,59 @@ impl<State: Send + Sync + 'static> Trek<State> {
         let state = Arc::new(self.state);
         let router = Arc::new(self.router);
         let not_found = Arc::new(NotFound::new());
+        let body_limit = self.body_limit;
+        let strict_content_type = self.strict_content_type;
+        let error_handler = self.error_handler;
 
         Ok(builder
             .serve(make_service_fn(move |_socket| {
                 let state = state.clone();
                 let router = router.clone();
                 let not_found = not_found.clone();
+                let error_handler = error_handler.clone();
 
                 async move {
                     Ok::<_, Infallible>(service_fn(move |req| {
                         let state = state.clone();
-                        let path = req.uri().path().to_owned();
-                        let method = req.method().to_owned();
-                        let middleware = router.middleware.clone();
-                        let mut cx = Context::new(state, req, vec![], middleware.clone());
-
-                        match router.find(&path, method) {
-                            Some((m, p)) => {
-                                cx.middleware.append(&mut m.clone());
-                                cx.params = p
-                                    .iter()
-                                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
-                                    .collect();
+                        let error_handler = error_handler.clone();
+                        let line = request_line(&req);
+                        let mut middleware = router.middleware.clone();
+                        let mut params = vec![];
+
+                        match router.route(&req) {
+                            Match::Found(m, p) => {
+                                middleware.append(&mut m.clone());
+                                params = p;
                             }
-                            None => {
-                                cx.middleware.push(not_found.clone());
+                            Match::Redirect(location) => {
+                                middleware.push(Arc::new(Redirect::new(location)));
+                            }
+                            Match::MethodNotAllowed(methods) => {
+                                middleware.push(Arc::new(MethodNotAllowed::new(methods)));
+                            }
+                            Match::NotFound => {
+                                middleware.push(not_found.clone());
                             }
                         };
 
-                        async move { Ok::<_, Error>(cx.next().await) }
+                        let mut cx = Context::new(state, req, params, middleware);
+                        cx.set_body_limit(body_limit);
+                        cx.set_strict_content_type(strict_content_type);
+                        let parts = error_handler.as_ref().map(|_| cx.parts_on_drop());
+
+                        async move {
+                            let mut res = cx.next().await;
+                            if let Some(err) = res.extensions_mut().remove::<crate::Error>() {
+                                log_error(&err, &line, &res);
+                                if let Some(handler) = error_handler {
+                                    let parts = parts
+                                        .and_then(|mut parts| parts.try_recv().ok().and_then(|x| x))
+                                        .unwrap_or(line);
+                                    res = handler(err, &parts);
+                                }
+                            }
+                            Ok::<_, Error>(res)
+                        }
                     }))
                 }
             }))
@@ -87,6 +167,26 @@ impl<State: Send + Sync + 'static> Trek<State> {
     }
 }
 
+/// Copies the request line, for logging errors.
+fn request_line(req: &Request) -> Parts {
+    http::Request::builder()
+        .method(req.method().clone())
+        .uri(req.uri().clone())
+        .version(req.version())
+        .body(())
+        .unwrap()
+        .into_parts()
+        .0
+}
+
+fn log_error(err: &crate::Error, parts: &Parts, res: &Response) {
+    if res.status().is_server_error() {
+        error!("{} {} {}: {}", parts.method, parts.uri, res.status(), err);
+    } else {
+        debug!("{} {} {}: {}", parts.method, parts.uri, res.status(), err);
+    }
+}
+
 impl Trek<()> {
     pub fn new() -> Self {
         Self::with_state(())
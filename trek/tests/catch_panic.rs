@@ -0,0 +1,62 @@
+use futures::{executor::block_on, future::BoxFuture};
+use hyper::body::to_bytes;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use trek::{
+    middleware::{CatchPanic, Panic},
+    Body, Context, Error, Middleware, Response, StatusCode,
+};
+
+fn boom(_: Context<()>) -> BoxFuture<'static, Response> {
+    Box::pin(async { panic!("boom") })
+}
+
+fn hello(_: Context<()>) -> BoxFuture<'static, Response> {
+    Box::pin(async { Response::new(Body::from("hello")) })
+}
+
+fn call(
+    catch_panic: &Arc<CatchPanic>,
+    handler: fn(Context<()>) -> BoxFuture<'static, Response>,
+) -> Response {
+    let req = http::Request::builder()
+        .uri("/users?page=2")
+        .body(Body::empty())
+        .unwrap();
+    let middleware: Vec<Arc<dyn Middleware<Context<()>>>> =
+        vec![catch_panic.clone(), Arc::new(handler)];
+    block_on(Context::new(Arc::new(()), req, vec![], middleware).next())
+}
+
+#[test]
+fn panic_is_a_server_error() {
+    let catch_panic = Arc::new(CatchPanic::new());
+    let mut res = call(&catch_panic, boom);
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let err = res.extensions_mut().remove::<Error>().unwrap();
+    assert_eq!(err.to_string(), "handler panicked on GET /users: boom");
+}
+
+#[test]
+fn later_requests_still_succeed() {
+    let panics = Arc::new(AtomicUsize::new(0));
+    let counter = panics.clone();
+    let catch_panic = Arc::new(CatchPanic::new().on_panic(move |panic: &Panic| {
+        assert_eq!(panic.message, "boom");
+        counter.fetch_add(1, Ordering::SeqCst);
+    }));
+
+    for _ in 0..2 {
+        let res = call(&catch_panic, boom);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let res = call(&catch_panic, hello);
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = block_on(to_bytes(res.into_body())).unwrap();
+        assert_eq!(&body[..], b"hello");
+    }
+    assert_eq!(panics.load(Ordering::SeqCst), 2);
+}
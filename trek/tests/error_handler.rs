@@ -0,0 +1,84 @@
+#![cfg(feature = "tokio")]
+
+use hyper::{body::to_bytes, Body, Client};
+use log::{Log, Metadata, Record};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use trek::{debug::RouteParams, Context, Rejection, Response, Result, StatusCode, Trek};
+
+type Events = Arc<Mutex<Vec<String>>>;
+
+/// Records the errors logged by the app.
+struct Logger(Events);
+
+impl Log for Logger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if record.target() == "trek::trek" {
+            let event = format!("{}: {}", record.level(), record.args());
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+async fn fail(_: Context<()>) -> Result {
+    Err(Rejection::InvalidQuery("nope".to_owned()).into())
+}
+
+#[test]
+fn error_handler_gets_the_parts_after_logging() {
+    let events = Events::default();
+    log::set_logger(Box::leak(Box::new(Logger(events.clone())))).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    let seen = events.clone();
+    let mut app = Trek::new();
+    app.router().get("/fail/:id", fail);
+    app.error_handler(move |err, parts| {
+        let params = parts.extensions.get::<RouteParams>().unwrap();
+        seen.lock().unwrap().push(format!(
+            "handler: {} {} {:?} {:?}",
+            parts.method, parts.uri, params.0, parts.headers["x-request-id"]
+        ));
+        let mut res = Response::new(Body::from(err.to_string()));
+        *res.status_mut() = StatusCode::IM_A_TEAPOT;
+        res
+    });
+
+    let mut rt = tokio::runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .unwrap();
+    let (status, body) = rt.block_on(async {
+        tokio::spawn(app.run("127.0.0.1:38471"));
+        tokio::time::delay_for(Duration::from_millis(100)).await;
+
+        let req = http::Request::get("http://127.0.0.1:38471/fail/3?q")
+            .header("x-request-id", "abc")
+            .body(Body::empty())
+            .unwrap();
+        let res = Client::new().request(req).await.unwrap();
+        let status = res.status();
+        (status, to_bytes(res.into_body()).await.unwrap())
+    });
+
+    assert_eq!(status, StatusCode::IM_A_TEAPOT);
+    assert_eq!(&body[..], b"invalid query string: nope");
+    let events = events.lock().unwrap();
+    let events: Vec<_> = events.iter().filter(|e| !e.starts_with("INFO")).collect();
+    assert_eq!(
+        events,
+        vec![
+            "DEBUG: GET /fail/3?q 400 Bad Request: invalid query string: nope",
+            "handler: GET /fail/3?q [(\"id\", \"3\")] \"abc\"",
+        ]
+    );
+}
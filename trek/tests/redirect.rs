@@ -0,0 +1,36 @@
+use futures::executor::block_on;
+use http::{header::LOCATION, Method, StatusCode};
+use std::sync::Arc;
+use trek::{middleware::Redirect, Body, Context, Middleware};
+
+fn redirect(method: Method) -> (StatusCode, String) {
+    let req = http::Request::builder()
+        .method(method)
+        .uri("/users/?page=2")
+        .body(Body::empty())
+        .unwrap();
+    let cx = Context::new(Arc::new(()), req, vec![], vec![]);
+    let res = block_on(Redirect::new("/users?page=2").call(cx));
+    let location = res.headers()[LOCATION].to_str().unwrap().to_owned();
+    (res.status(), location)
+}
+
+#[test]
+fn moved_permanently_for_get_and_head() {
+    for method in vec![Method::GET, Method::HEAD] {
+        assert_eq!(
+            redirect(method),
+            (StatusCode::MOVED_PERMANENTLY, "/users?page=2".to_owned())
+        );
+    }
+}
+
+#[test]
+fn permanent_redirect_keeps_other_methods() {
+    for method in vec![Method::POST, Method::PUT, Method::DELETE] {
+        assert_eq!(
+            redirect(method),
+            (StatusCode::PERMANENT_REDIRECT, "/users?page=2".to_owned())
+        );
+    }
+}